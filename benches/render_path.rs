@@ -0,0 +1,76 @@
+//! What the UI reads from every program on every frame
+//!
+//! Run with `cargo bench`. Each read should cost the same whatever the
+//! window length, since `ProgramStats` keeps running totals.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use soltop::stats::{CuMode, CuSketch, ProgramStats, SlotStats};
+use std::time::{Duration, Instant};
+
+fn slot(i: u64, start: Instant) -> SlotStats {
+    let mut sketch = CuSketch::new();
+    for tx in 0..8 {
+        sketch.insert(5_000 + (i * 7919 + tx * 104_729) % 400_000);
+    }
+    SlotStats {
+        timestamp: start + Duration::from_millis(i * 400),
+        slot: i,
+        block_time: Some(1_700_000_000 + (i * 2 / 5) as i64),
+        total_cu: sketch.count() * 200_000,
+        tx_count: 8,
+        success_count: 7,
+        invocation_count: 9,
+        program_failure_count: 1,
+        errors: vec![("Custom 0x1771 (6001)".to_string(), 1)],
+        avg_cu: 200_000.0,
+        min_cu: sketch.min(),
+        max_cu: sketch.max(),
+        cu_sketch: sketch.clone(),
+        exclusive_cu: sketch.count() * 150_000,
+        exclusive_min_cu: sketch.min(),
+        exclusive_max_cu: sketch.max(),
+        exclusive_cu_sketch: sketch,
+    }
+}
+
+fn render_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("program_stats_render");
+    let start = Instant::now();
+
+    for window in [75usize, 750, 7_500] {
+        let mut stats = ProgramStats::new(
+            "JUP4Fb2c".to_string(),
+            window,
+            Duration::from_secs(window as u64),
+        );
+        // Fill the window twice over so eviction has run
+        for i in 0..(window as u64 * 2) {
+            stats.record_slot(slot(i, start));
+        }
+
+        group.bench_with_input(BenchmarkId::from_parameter(window), &stats, |b, stats| {
+            b.iter(|| {
+                let mode = CuMode::Exclusive;
+                black_box((
+                    stats.total_transactions(),
+                    stats.total_invocations(),
+                    stats.transactions_per_second(),
+                    stats.cu_per_second_for(mode),
+                    stats.avg_cu_per_transaction_for(mode),
+                    stats.min_cu_for(mode),
+                    stats.max_cu_for(mode),
+                    stats.cu_percentile_for(mode, 0.5),
+                    stats.cu_percentile_for(mode, 0.9),
+                    stats.cu_percentile_for(mode, 0.99),
+                    stats.success_rate(),
+                    stats.program_failure_rate(),
+                ))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, render_path);
+criterion_main!(benches);
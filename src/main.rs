@@ -6,9 +6,11 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use std::sync::Arc;
 use std::time::Duration;
 
-use soltop::ui::App;
+use soltop::rpc::RetryConfig;
+use soltop::ui::{App, Filter};
 use soltop::{MonitorConfig, NetworkMonitor};
 
 #[derive(Parser, Debug)]
@@ -19,17 +21,51 @@ struct Args {
     #[arg(short, long)]
     verbose: bool,
 
-    /// RPC endpoint URL
+    /// RPC endpoint URL(s)
     #[arg(
         long,
+        num_args = 1..,
+        value_delimiter = ',',
         default_value = "https://api.mainnet-beta.solana.com",
-        help = "RPC endpoint URL"
+        help = "RPC endpoint URL (repeat or comma-separate for failover)"
     )]
-    rpc_url: String,
+    rpc_url: Vec<String>,
 
     /// Hide system programs (Vote, ComputeBudget, System)
     #[arg(long)]
     hide_system: bool,
+
+    /// Receive new slots over WebSocket (slotSubscribe/rootSubscribe) instead of polling
+    #[arg(long)]
+    websocket: bool,
+
+    /// WebSocket endpoint URL (implies --websocket; derived from --rpc-url if omitted)
+    #[arg(long)]
+    ws_url: Option<String>,
+
+    /// Number of blocks fetched concurrently
+    #[arg(long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// Also count programs invoked via CPI (inner instructions)
+    #[arg(long)]
+    inner_instructions: bool,
+
+    /// Most programs tracked at once; the least active are dropped beyond this
+    #[arg(long, default_value_t = 5_000)]
+    max_programs: usize,
+
+    /// Seconds of recent activity shown in Live mode
+    #[arg(long, default_value_t = 10)]
+    live_horizon: u64,
+
+    /// Only show programs matching this expression (e.g. "success < 90 && avg_cu > 100k")
+    #[arg(long, value_parser = Filter::parse)]
+    filter: Option<Filter>,
+
+    /// Retries per RPC request on transient errors (429, 5xx, timeouts)
+    #[arg(long, default_value_t = 5)]
+    max_retries: u32,
 }
 
 #[tokio::main]
@@ -39,10 +75,20 @@ async fn main() -> Result<()> {
 
     // Create configuration
     let config = MonitorConfig {
-        rpc_url: args.rpc_url,
+        rpc_urls: args.rpc_url,
         window_duration: Duration::from_secs(5 * 60), // 5 minutes
         buffer_capacity: 750,
         poll_interval: Duration::from_millis(400),
+        retry_config: RetryConfig {
+            max_retries: args.max_retries,
+            ..RetryConfig::default()
+        },
+        websocket: args.websocket || args.ws_url.is_some(),
+        ws_url: args.ws_url,
+        fetch_concurrency: args.concurrency.max(1),
+        count_inner_instructions: args.inner_instructions,
+        max_programs: args.max_programs,
+        verbose: args.verbose,
     };
 
     // Create monitor
@@ -66,7 +112,11 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create app with the shared state
-    let mut app = App::new(network_state);
+    let mut app = App::new(Arc::clone(&network_state))
+        .with_live_horizon(Duration::from_secs(args.live_horizon.max(1)));
+    if let Some(filter) = args.filter {
+        app = app.with_filter(filter);
+    }
 
     // Run the app
     let result = app.run(&mut terminal).await;
@@ -80,5 +130,9 @@ async fn main() -> Result<()> {
         eprintln!("Application error: {}", e);
     }
 
+    if args.verbose {
+        network_state.read().await.print_perf_summary();
+    }
+
     Ok(())
 }
@@ -1,38 +1,46 @@
-use anyhow::{Context, Result};
 use reqwest;
+use reqwest::header::RETRY_AFTER;
 use serde_json::json;
+use std::time::Duration;
 
-use super::types::{BlockResponse, SlotResponse};
+use super::error::{RetryConfig, RpcError};
+use super::types::{BlockData, RpcResponse};
 
 /// Client for interacting with Solana RPC endpoints
 pub struct RpcClient {
     url: String,
     client: reqwest::Client,
+    retry_config: RetryConfig,
 }
 
 impl RpcClient {
-    /// Create a new RPC client
+    /// Create a new RPC client with the default retry policy
     pub fn new(url: String) -> Self {
+        Self::with_retry_config(url, RetryConfig::default())
+    }
+
+    /// Create a new RPC client with a custom retry policy
+    pub fn with_retry_config(url: String, retry_config: RetryConfig) -> Self {
         Self {
             url,
             client: reqwest::Client::new(),
+            retry_config,
         }
     }
 
     /// Fetch the latest slot number
-    pub async fn get_latest_slot(&self) -> Result<u64> {
+    pub async fn get_latest_slot(&self) -> Result<u64, RpcError> {
         let params = json!([]);
 
-        let response: SlotResponse = self
-            .call_rpc("getSlot", params)
-            .await
-            .context("Failed to get latest slot")?;
-
-        Ok(response.result)
+        self.call_rpc::<u64>("getSlot", params)
+            .await?
+            .ok_or_else(|| RpcError::Parse("getSlot returned no result".to_string()))
     }
 
     /// Fetch block data for a given slot
-    pub async fn get_block(&self, slot: u64) -> Result<Option<BlockResponse>> {
+    ///
+    /// Returns `Ok(None)` when the slot was skipped (no block will ever exist).
+    pub async fn get_block(&self, slot: u64) -> Result<Option<BlockData>, RpcError> {
         let params = json!([slot, {
             "encoding": "json",
             "transactionDetails": "full",
@@ -40,20 +48,45 @@ impl RpcClient {
             "maxSupportedTransactionVersion": 0
         }]);
 
-        let response: BlockResponse = self
-            .call_rpc("getBlock", params)
-            .await
-            .context(format!("Failed to get block {}", slot))?;
-
-        Ok(Some(response))
+        match self.call_rpc::<BlockData>("getBlock", params).await {
+            Ok(block) => Ok(block),
+            Err(RpcError::SlotSkipped { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 
-    /// Helper: Make a JSON-RPC request
+    /// Helper: Make a JSON-RPC request, retrying transient failures
     async fn call_rpc<T: serde::de::DeserializeOwned>(
         &self,
         method: &str,
         params: serde_json::Value,
-    ) -> Result<T> {
+    ) -> Result<Option<T>, RpcError> {
+        let mut attempt = 0;
+
+        loop {
+            match self.call_rpc_once(method, &params).await {
+                Err(e) if e.is_retryable() && attempt < self.retry_config.max_retries => {
+                    // Honor Retry-After when the server sends one, but never stall
+                    // the pipeline for longer than our own backoff ceiling
+                    let delay = e
+                        .retry_after()
+                        .map(|d| d.min(self.retry_config.max_backoff))
+                        .unwrap_or_else(|| self.retry_config.backoff(attempt));
+
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Helper: Make a single JSON-RPC request (no retries)
+    async fn call_rpc_once<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> Result<Option<T>, RpcError> {
         let request_body = json!({
             "jsonrpc": "2.0",
             "id": "1",
@@ -66,15 +99,32 @@ impl RpcClient {
             .post(&self.url)
             .json(&request_body)
             .send()
-            .await
-            .context("Failed to send RPC request")?;
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+
+            return Err(RpcError::Http {
+                status: status.as_u16(),
+                retry_after,
+            });
+        }
 
-        let parsed = response
-            .json::<T>()
-            .await
-            .context("Failed to parse RPC response")?;
+        let body = response.bytes().await?;
+        let parsed: RpcResponse<T> =
+            serde_json::from_slice(&body).map_err(|e| RpcError::Parse(e.to_string()))?;
+
+        if let Some(error) = parsed.error {
+            return Err(RpcError::from_json_rpc(error.code, error.message));
+        }
 
-        Ok(parsed)
+        Ok(parsed.result)
     }
 }
 
@@ -114,8 +164,7 @@ mod tests {
 
         assert!(block.is_some(), "Block should exist");
 
-        if let Some(block_response) = block {
-            let block_data = block_response.result.expect("Block should have data");
+        if let Some(block_data) = block {
             println!("Block has {} transactions", block_data.transactions.len());
             assert!(
                 !block_data.transactions.is_empty(),
@@ -0,0 +1,214 @@
+use std::fmt;
+use std::time::Duration;
+
+/// JSON-RPC error code: slot was skipped by the leader (no block produced)
+pub const SLOT_SKIPPED: i64 = -32007;
+
+/// JSON-RPC error code: slot skipped, or missing due to ledger jump to snapshot
+pub const LONG_TERM_STORAGE_SLOT_SKIPPED: i64 = -32009;
+
+/// JSON-RPC error code: block not available yet for this slot
+pub const BLOCK_NOT_AVAILABLE: i64 = -32004;
+
+/// Errors returned by the RPC client
+#[derive(Debug)]
+pub enum RpcError {
+    /// The request never produced an HTTP response (DNS, connect, timeout, ...)
+    Transport(reqwest::Error),
+
+    /// The endpoint answered with a non-success HTTP status (e.g. 429, 503)
+    Http {
+        status: u16,
+        retry_after: Option<Duration>,
+    },
+
+    /// The endpoint returned a JSON-RPC error object we don't special-case
+    JsonRpc { code: i64, message: String },
+
+    /// The requested slot was skipped (-32007 / -32009), there will never be a block
+    SlotSkipped { code: i64, message: String },
+
+    /// The block for this slot is not available yet (-32004)
+    BlockNotAvailable { message: String },
+
+    /// The response body could not be parsed
+    Parse(String),
+}
+
+impl RpcError {
+    /// Map a JSON-RPC error object to the matching variant
+    pub fn from_json_rpc(code: i64, message: String) -> Self {
+        match code {
+            SLOT_SKIPPED | LONG_TERM_STORAGE_SLOT_SKIPPED => Self::SlotSkipped { code, message },
+            BLOCK_NOT_AVAILABLE => Self::BlockNotAvailable { message },
+            _ => Self::JsonRpc { code, message },
+        }
+    }
+
+    /// Whether retrying the same request may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Transport(_) => true,
+            Self::Http { status, .. } => *status == 429 || *status >= 500,
+            Self::BlockNotAvailable { .. } => true,
+            Self::JsonRpc { .. } | Self::SlotSkipped { .. } | Self::Parse(_) => false,
+        }
+    }
+
+    /// Server-provided delay before retrying (from the Retry-After header)
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Http { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(e) => write!(f, "transport error: {}", e),
+            Self::Http { status, .. } => write!(f, "HTTP status {}", status),
+            Self::JsonRpc { code, message } => write!(f, "JSON-RPC error {}: {}", code, message),
+            Self::SlotSkipped { code, message } => {
+                write!(f, "slot skipped ({}): {}", code, message)
+            }
+            Self::BlockNotAvailable { message } => write!(f, "block not available: {}", message),
+            Self::Parse(msg) => write!(f, "failed to parse RPC response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Transport(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for RpcError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Transport(e)
+    }
+}
+
+/// Retry policy for RPC requests (exponential backoff with jitter)
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Retries after the first attempt (0 disables retrying)
+    pub max_retries: u32,
+
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+
+    /// Upper bound for any single delay
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// No retries: every error is returned immediately
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `attempt` (0-based)
+    ///
+    /// Uses "equal jitter": half of the exponential delay is fixed, the other
+    /// half is random, so concurrent clients don't retry in lockstep.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.min(16)));
+        let capped = exp.min(self.max_backoff);
+
+        let half = capped / 2;
+        let jitter = half.mul_f64(fastrand::f64());
+        half + jitter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_rpc_code_mapping() {
+        assert!(matches!(
+            RpcError::from_json_rpc(-32007, "skipped".into()),
+            RpcError::SlotSkipped { code: -32007, .. }
+        ));
+        assert!(matches!(
+            RpcError::from_json_rpc(-32009, "skipped".into()),
+            RpcError::SlotSkipped { code: -32009, .. }
+        ));
+        assert!(matches!(
+            RpcError::from_json_rpc(-32004, "not yet".into()),
+            RpcError::BlockNotAvailable { .. }
+        ));
+        assert!(matches!(
+            RpcError::from_json_rpc(-32602, "invalid params".into()),
+            RpcError::JsonRpc { code: -32602, .. }
+        ));
+    }
+
+    #[test]
+    fn test_retryable_classification() {
+        let rate_limited = RpcError::Http {
+            status: 429,
+            retry_after: None,
+        };
+        let unavailable = RpcError::Http {
+            status: 503,
+            retry_after: None,
+        };
+        let forbidden = RpcError::Http {
+            status: 403,
+            retry_after: None,
+        };
+
+        assert!(rate_limited.is_retryable());
+        assert!(unavailable.is_retryable());
+        assert!(!forbidden.is_retryable());
+        assert!(RpcError::from_json_rpc(-32004, String::new()).is_retryable());
+        assert!(!RpcError::from_json_rpc(-32007, String::new()).is_retryable());
+        assert!(!RpcError::Parse("bad json".into()).is_retryable());
+    }
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let config = RetryConfig {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+
+        for attempt in 0..10 {
+            let exp = Duration::from_millis(100 * 2u64.pow(attempt)).min(Duration::from_secs(1));
+            let delay = config.backoff(attempt);
+
+            // Equal jitter: delay is within [exp/2, exp]
+            assert!(
+                delay >= exp / 2,
+                "attempt {}: {:?} too short",
+                attempt,
+                delay
+            );
+            assert!(delay <= exp, "attempt {}: {:?} too long", attempt, delay);
+        }
+    }
+}
@@ -1,11 +1,27 @@
 //! RPC client for interacting with Solana nodes
 //!
-//! This module provides functionality to fetch slots and blocks from any Solana RPC endpoint.
+//! This module provides functionality to fetch slots and blocks from any Solana RPC endpoint,
+//! or from a pool of endpoints with health-based failover.
 
 mod client;
+mod error;
 mod parser;
+mod pool;
+mod source;
+mod transaction_error;
 mod types;
+mod websocket;
 
 pub use client::RpcClient;
-pub use parser::{extract_program_cu, extract_program_cu_timed};
-pub use types::{BlockData, LogMessage, SlotResponse, TransactionData};
+pub use error::{RetryConfig, RpcError};
+pub use parser::{
+    parse_log_line, reconcile_cu, summarize_logs, AnchorErrorLog, LogLine, LogSummary, ProgramCu,
+    LOG_TRUNCATED,
+};
+pub use pool::{EndpointStatus, RpcPool};
+pub use source::{BlockSource, SlotSource};
+pub use transaction_error::{anchor_error_name, InstructionError, TransactionError};
+pub use types::{
+    AccountKeys, BlockData, Legacy, LogMessage, SlotResponse, TransactionData, TransactionVersion,
+};
+pub use websocket::{ws_url_from_http, SlotSubscription};
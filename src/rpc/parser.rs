@@ -1,83 +1,664 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
+
+// Every pattern below is anchored at both ends and only accepts base58 program
+// IDs. Programs can only write to the log through `Program log:` /
+// `Program data:` lines, so an anchored match can't be forged by a program
+// printing something that merely contains runtime-looking text.
 
 /// Regex to match compute unit consumption in logs
 /// Matches: "Program XXX consumed 12345 of 200000 compute units"
-static CU_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"Program (\S+) consumed (\d+) of \d+ compute units").unwrap());
-
-/// Extract program ID and CU consumption from a log message
-/// Returns None if the log doesn't contain CU information
-fn parse_program_cu(log: &str) -> Option<(String, u64)> {
-    let caps = CU_REGEX.captures(log)?;
-    let program_id = caps.get(1)?.as_str().to_string();
-    let cu = caps.get(2)?.as_str().parse().ok()?;
-    Some((program_id, cu)) // Option<u64>
+static CU_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Program ([1-9A-HJ-NP-Za-km-z]{32,44}) consumed (\d+) of (\d+) compute units$")
+        .unwrap()
+});
+
+/// Regex to match a program invocation and its CPI depth
+/// Matches: "Program XXX invoke [1]"
+static INVOKE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Program ([1-9A-HJ-NP-Za-km-z]{32,44}) invoke \[(\d+)\]$").unwrap());
+
+/// Regex to match a successful end of an invocation
+/// Matches: "Program XXX success"
+static SUCCESS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Program ([1-9A-HJ-NP-Za-km-z]{32,44}) success$").unwrap());
+
+/// Regex to match a failed end of an invocation
+/// Matches: "Program XXX failed: custom program error: 0x1"
+static FAILED_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)^Program ([1-9A-HJ-NP-Za-km-z]{32,44}) failed: (.*)$").unwrap());
+
+/// Regex to match return data set by a program
+/// Matches: "Program return: XXX AQAAAA=="
+static RETURN_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Program return: ([1-9A-HJ-NP-Za-km-z]{32,44}) (\S*)$").unwrap());
+
+/// Regex to match the `sol_log_compute_units` syscall
+/// Matches: "Program consumption: 12345 units remaining"
+static CONSUMPTION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Program consumption: (\d+) units remaining$").unwrap());
+
+/// Regex to match Anchor's error report in a program's `Program log:` output
+/// Matches: "AnchorError thrown in src/lib.rs:42. Error Code: Foo. Error Number: 6001. Error Message: ..."
+static ANCHOR_ERROR_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^AnchorError\b.*? Error Code: (\w+)\. Error Number: (\d+)\. Error Message: ")
+        .unwrap()
+});
+
+/// Line the runtime appends when a transaction's logs exceed the size limit
+pub const LOG_TRUNCATED: &str = "Log truncated";
+
+/// One line of a transaction's logs, classified by the runtime's log grammar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogLine<'a> {
+    /// `Program <id> invoke [<depth>]`
+    Invoke { program_id: &'a str, depth: usize },
+
+    /// `Program <id> consumed <cu> of <budget> compute units`
+    Consumed {
+        program_id: &'a str,
+        cu: u64,
+        budget: u64,
+    },
+
+    /// `Program <id> success`
+    Success { program_id: &'a str },
+
+    /// `Program <id> failed: <error>`
+    Failed { program_id: &'a str, error: &'a str },
+
+    /// `Program log: <message>` (written by the running program)
+    Log(&'a str),
+
+    /// `Program data: <base64...>` (written by the running program)
+    Data(&'a str),
+
+    /// `Program return: <id> <base64>`
+    Return { program_id: &'a str, data: &'a str },
+
+    /// `Program consumption: <units> units remaining`
+    Consumption(u64),
+
+    /// `Log truncated`
+    Truncated,
+
+    /// Anything else (builtin messages, or lines we don't understand)
+    Other(&'a str),
 }
 
-/// Extract ALL programs and their TOTAL CU consumption from logs
+/// Classify one log line
 ///
-/// If a program appears multiple times, CU values are summed.
-pub fn extract_program_cu(logs: &[String]) -> HashMap<String, u64> {
-    let mut programs = HashMap::new();
+/// Only exact runtime formats are recognized; a program ID must be valid
+/// base58 and numbers must fit in a u64, otherwise the line is `Other`.
+pub fn parse_log_line(line: &str) -> LogLine<'_> {
+    if line == LOG_TRUNCATED {
+        return LogLine::Truncated;
+    }
+    // Program-written lines first: whatever follows the prefix is opaque
+    if let Some(message) = line.strip_prefix("Program log: ") {
+        return LogLine::Log(message);
+    }
+    if let Some(data) = line.strip_prefix("Program data: ") {
+        return LogLine::Data(data);
+    }
+
+    if let Some(caps) = INVOKE_REGEX.captures(line) {
+        if let Ok(depth) = caps[2].parse() {
+            return LogLine::Invoke {
+                program_id: caps.get(1).unwrap().as_str(),
+                depth,
+            };
+        }
+    } else if let Some(caps) = CU_REGEX.captures(line) {
+        if let (Ok(cu), Ok(budget)) = (caps[2].parse(), caps[3].parse()) {
+            return LogLine::Consumed {
+                program_id: caps.get(1).unwrap().as_str(),
+                cu,
+                budget,
+            };
+        }
+    } else if let Some(caps) = SUCCESS_REGEX.captures(line) {
+        return LogLine::Success {
+            program_id: caps.get(1).unwrap().as_str(),
+        };
+    } else if let Some(caps) = FAILED_REGEX.captures(line) {
+        return LogLine::Failed {
+            program_id: caps.get(1).unwrap().as_str(),
+            error: caps.get(2).unwrap().as_str(),
+        };
+    } else if let Some(caps) = RETURN_REGEX.captures(line) {
+        return LogLine::Return {
+            program_id: caps.get(1).unwrap().as_str(),
+            data: caps.get(2).unwrap().as_str(),
+        };
+    } else if let Some(caps) = CONSUMPTION_REGEX.captures(line) {
+        if let Ok(units) = caps[1].parse() {
+            return LogLine::Consumption(units);
+        }
+    }
+
+    LogLine::Other(line)
+}
+
+/// CU attributed to one program within a transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProgramCu {
+    /// As logged: includes the CU of every program it invoked via CPI
+    pub inclusive: u64,
+
+    /// Self CU: inclusive minus the inclusive CU of its direct CPI callees
+    pub exclusive: u64,
+}
+
+/// One open invocation on the CPI stack
+struct Frame {
+    program_id: String,
+    /// Inclusive CU reported by direct children so far
+    child_cu: u64,
+    /// The "consumed" line has been seen (the runtime logs exactly one)
+    consumed: bool,
+}
+
+/// An error a program reported through Anchor's `AnchorError ...` log line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorErrorLog {
+    /// Program that logged it
+    pub program_id: String,
+
+    /// Error variant name, e.g. "SlippageToleranceExceeded"
+    pub name: String,
+
+    /// Error code, matching `Custom(code)` in the transaction error
+    pub code: u32,
+}
+
+/// CU information recovered from one transaction's logs
+#[derive(Debug, Default)]
+pub struct LogSummary {
+    /// Program ID -> summed CU (inclusive and exclusive) over all its invocations
+    pub program_cu: HashMap<String, ProgramCu>,
+
+    /// Top-level invocations in order, with their consumed CU if it was logged
+    pub top_level: Vec<(String, Option<u64>)>,
+
+    /// Logs were cut short by the runtime ("Log truncated")
+    pub truncated: bool,
+
+    /// Runtime-format lines that didn't fit the invoke stack and were ignored
+    pub rejected_lines: usize,
+
+    /// Program that raised the transaction's error
+    ///
+    /// A failing CPI makes every caller log `failed` too, so this is the
+    /// innermost one: the first `failed` line.
+    pub failed_program: Option<String>,
+
+    /// First Anchor error report logged by a running program
+    pub anchor_error: Option<AnchorErrorLog>,
+}
+
+impl LogSummary {
+    /// CU accounted for by top-level "consumed" lines
+    pub fn top_level_cu(&self) -> u64 {
+        self.top_level.iter().filter_map(|(_, cu)| *cu).sum()
+    }
+}
+
+/// Summarize a transaction's logs: per-program CU plus top-level invocations
+///
+/// Tracks the CPI stack from `invoke [depth]` / `success` / `failed` lines so
+/// each "consumed" line can be split into the program's own CU (exclusive)
+/// and the CU of the programs it called. Summing inclusive figures across a
+/// call tree double counts; summing exclusive figures does not.
+///
+/// A runtime line is only accepted where the runtime could have written it:
+/// an invoke must be exactly one level deeper than the current frame, and
+/// consumed / success / failed / return lines must name the running program.
+/// Anything else is counted in `rejected_lines` and ignored.
+pub fn summarize_logs(logs: &[String]) -> LogSummary {
+    let mut summary = LogSummary::default();
+    let mut stack: Vec<Frame> = Vec::new();
 
     for log in logs {
-        if let Some((program_id, cu)) = parse_program_cu(log) {
-            *programs.entry(program_id).or_insert(0) += cu;
+        let running = stack.last().map(|frame| frame.program_id.as_str());
+
+        match parse_log_line(log) {
+            LogLine::Truncated => {
+                // Nothing the runtime writes after this
+                summary.truncated = true;
+                break;
+            }
+            LogLine::Invoke { program_id, depth } if depth == stack.len() + 1 => {
+                stack.push(Frame {
+                    program_id: program_id.to_string(),
+                    child_cu: 0,
+                    consumed: false,
+                });
+
+                if depth == 1 {
+                    summary.top_level.push((program_id.to_string(), None));
+                }
+            }
+            LogLine::Consumed { program_id, cu, .. }
+                if running == Some(program_id) && !stack.last().unwrap().consumed =>
+            {
+                let frame = stack.last_mut().unwrap();
+                frame.consumed = true;
+                let child_cu = frame.child_cu;
+
+                let entry = summary
+                    .program_cu
+                    .entry(program_id.to_string())
+                    .or_default();
+                entry.inclusive += cu;
+                entry.exclusive += cu.saturating_sub(child_cu);
+
+                // Credit the caller, or record the top-level total
+                match stack.len() {
+                    1 => {
+                        if let Some((_, consumed)) = summary.top_level.last_mut() {
+                            *consumed = Some(cu);
+                        }
+                    }
+                    depth => stack[depth - 2].child_cu += cu,
+                }
+            }
+            LogLine::Success { program_id } if running == Some(program_id) => {
+                stack.pop();
+            }
+            LogLine::Failed { program_id, .. } if running == Some(program_id) => {
+                if summary.failed_program.is_none() {
+                    summary.failed_program = Some(program_id.to_string());
+                }
+                stack.pop();
+            }
+            LogLine::Return { program_id, .. } if running == Some(program_id) => {}
+            LogLine::Log(message) if running.is_some() => {
+                if summary.anchor_error.is_none() {
+                    summary.anchor_error =
+                        parse_anchor_error(message).map(|(name, code)| AnchorErrorLog {
+                            program_id: running.unwrap_or_default().to_string(),
+                            name: name.to_string(),
+                            code,
+                        });
+                }
+            }
+            LogLine::Data(_) | LogLine::Consumption(_) if running.is_some() => {}
+            // Builtins log free-form messages; they can't mimic the runtime
+            LogLine::Other(_) => {}
+            _ => summary.rejected_lines += 1,
         }
     }
 
-    programs
+    summary
 }
 
-// timed version
-pub fn extract_program_cu_timed(logs: &[String]) -> (HashMap<String, u64>, Duration) {
-    let start = Instant::now();
-    let result = extract_program_cu(logs);
-    let elapsed = start.elapsed();
-    (result, elapsed)
+/// Error name and code from an Anchor `AnchorError ...` log message
+fn parse_anchor_error(message: &str) -> Option<(&str, u32)> {
+    let caps = ANCHOR_ERROR_REGEX.captures(message)?;
+    let code = caps[2].parse().ok()?;
+    Some((caps.get(1)?.as_str(), code))
+}
+
+/// Reconcile log-derived CU against the transaction's `computeUnitsConsumed`
+///
+/// Top-level instructions whose "consumed" line is missing (logs truncated or
+/// absent, or builtins that don't log) split the CU the logs can't explain.
+/// `instruction_programs` are the top-level instruction program IDs in order;
+/// the i-th depth-1 invocation in the logs belongs to the i-th instruction.
+/// Reconciled CU counts as both inclusive and exclusive, since we can't see
+/// which callees it went to.
+pub fn reconcile_cu(
+    summary: &LogSummary,
+    tx_total_cu: Option<u64>,
+    instruction_programs: &[String],
+) -> HashMap<String, ProgramCu> {
+    let mut programs = summary.program_cu.clone();
+
+    let Some(total) = tx_total_cu else {
+        return programs;
+    };
+    let gap = total.saturating_sub(summary.top_level_cu());
+    if gap == 0 {
+        return programs;
+    }
+
+    let unaccounted: Vec<&String> = instruction_programs
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !matches!(summary.top_level.get(*i), Some((_, Some(_)))))
+        .map(|(_, program_id)| program_id)
+        .collect();
+
+    if unaccounted.is_empty() {
+        return programs;
+    }
+
+    // Even split; the remainder goes to the first unaccounted instruction
+    let share = gap / unaccounted.len() as u64;
+    let remainder = gap % unaccounted.len() as u64;
+    for (i, program_id) in unaccounted.into_iter().enumerate() {
+        let cu = if i == 0 { share + remainder } else { share };
+        let entry = programs.entry(program_id.clone()).or_default();
+        entry.inclusive += cu;
+        entry.exclusive += cu;
+    }
+
+    programs
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
     #[test]
-    fn test_multi_program_extraction() {
-        let logs = vec![
-            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 3158 of 200207 compute units".to_string(),
-            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 7913 of 204938 compute units".to_string(),
-        ];
+    fn test_summarize_tracks_top_level_invocations() {
+        let summary = summarize_logs(&logs(&[
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 3000 of 180000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 20000 of 200000 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+        ]));
 
-        let programs = extract_program_cu(&logs);
+        assert!(!summary.truncated);
+        assert_eq!(
+            summary.top_level,
+            vec![
+                (
+                    "ComputeBudget111111111111111111111111111111".to_string(),
+                    None
+                ),
+                (
+                    "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+                    Some(20000)
+                ),
+            ]
+        );
+        assert_eq!(summary.top_level_cu(), 20000);
+    }
+
+    #[test]
+    fn test_reconcile_truncated_logs() {
+        // Second instruction's logs were cut off before its "consumed" line
+        let summary = summarize_logs(&logs(&[
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 20000 of 200000 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+            "Program 675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8 invoke [1]",
+            "Program log: ray_log: A0Bx",
+            "Log truncated",
+        ]));
+        assert!(summary.truncated);
+
+        let programs = reconcile_cu(
+            &summary,
+            Some(65000),
+            &[
+                "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+                "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8".to_string(),
+            ],
+        );
+
+        assert_eq!(
+            programs["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"].inclusive,
+            20000
+        );
+        assert_eq!(
+            programs["675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8"],
+            ProgramCu {
+                inclusive: 45000,
+                exclusive: 45000
+            }
+        );
+    }
+
+    #[test]
+    fn test_reconcile_missing_logs() {
+        let programs = reconcile_cu(
+            &LogSummary::default(),
+            Some(10001),
+            &[
+                "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+                "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8".to_string(),
+            ],
+        );
+
+        // Even split, remainder to the first instruction
+        assert_eq!(
+            programs["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"].inclusive,
+            5001
+        );
+        assert_eq!(
+            programs["675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8"].inclusive,
+            5000
+        );
+    }
+
+    #[test]
+    fn test_reconcile_without_total_keeps_logs() {
+        let summary = summarize_logs(&logs(&[
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 20000 of 200000 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+        ]));
+
+        let programs = reconcile_cu(&summary, None, &[]);
+        assert_eq!(programs, summary.program_cu);
+    }
+
+    #[test]
+    fn test_inclusive_vs_exclusive_cu() {
+        // Aggregator -> AMM -> Token (x2), plus a direct Token call from the aggregator
+        let summary = summarize_logs(&logs(&[
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [3]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4000 of 150000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [3]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 5000 of 140000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc consumed 40000 of 170000 compute units",
+            "Program whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc success",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 3000 of 120000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: insufficient funds",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 60000 of 200000 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1",
+        ]));
+
+        let jupiter = summary.program_cu["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"];
+        let orca = summary.program_cu["whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"];
+        let token = summary.program_cu["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"];
+
+        assert_eq!(
+            jupiter,
+            ProgramCu {
+                inclusive: 60000,
+                exclusive: 17000
+            }
+        );
+        assert_eq!(
+            orca,
+            ProgramCu {
+                inclusive: 40000,
+                exclusive: 31000
+            }
+        );
+        assert_eq!(
+            token,
+            ProgramCu {
+                inclusive: 12000,
+                exclusive: 12000
+            }
+        );
+
+        // Exclusive CU adds up to the transaction total; inclusive double counts
+        let exclusive_total: u64 = summary.program_cu.values().map(|c| c.exclusive).sum();
+        assert_eq!(exclusive_total, summary.top_level_cu());
+    }
+
+    #[test]
+    fn test_parse_log_line() {
+        let jup = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+        assert_eq!(
+            parse_log_line(&format!("Program {} invoke [2]", jup)),
+            LogLine::Invoke {
+                program_id: jup,
+                depth: 2
+            }
+        );
+        assert_eq!(
+            parse_log_line(&format!(
+                "Program {} consumed 7913 of 204938 compute units",
+                jup
+            )),
+            LogLine::Consumed {
+                program_id: jup,
+                cu: 7913,
+                budget: 204938
+            }
+        );
+        assert_eq!(
+            parse_log_line(&format!(
+                "Program {} failed: custom program error: 0x1771",
+                jup
+            )),
+            LogLine::Failed {
+                program_id: jup,
+                error: "custom program error: 0x1771"
+            }
+        );
+        assert_eq!(
+            parse_log_line(&format!("Program return: {} AQAAAA==", jup)),
+            LogLine::Return {
+                program_id: jup,
+                data: "AQAAAA=="
+            }
+        );
+        assert_eq!(
+            parse_log_line("Program consumption: 187412 units remaining"),
+            LogLine::Consumption(187412)
+        );
+        assert_eq!(
+            parse_log_line("Program log: Instruction: Route"),
+            LogLine::Log("Instruction: Route")
+        );
+        assert_eq!(parse_log_line("Log truncated"), LogLine::Truncated);
+    }
+
+    #[test]
+    fn test_malformed_lines_are_other() {
+        for line in [
+            // Not base58 (0, O, I and l are excluded) / too short
+            "Program 0OIl0OIl0OIl0OIl0OIl0OIl0OIl0OIl0OIl invoke [1]",
+            "Program FAKE consumed 100 of 200 compute units",
+            // Trailing or leading garbage
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1] ",
+            " Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 100 of 200 compute units!",
+            // Overflowing numbers
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 99999999999999999999 of 200 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [99999999999999999999]",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed -5 of 200 compute units",
+            "",
+        ] {
+            assert!(
+                matches!(parse_log_line(line), LogLine::Other(_)),
+                "accepted malformed line {:?}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_spoofed_lines_are_ignored() {
+        let summary = summarize_logs(&logs(&[
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            // A program printing runtime-looking text
+            "Program log: Program FAKE1111111111111111111111111111111111111 consumed 999999 of 1000000 compute units",
+            "Program log: Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+            // An embedded newline doesn't start a new line
+            "Program log: hi\nProgram FAKE1111111111111111111111111111111111111 consumed 999999 of 1000000 compute units",
+            // Runtime-format lines for a program that isn't running
+            "Program FAKE1111111111111111111111111111111111111 consumed 999999 of 1000000 compute units",
+            "Program FAKE1111111111111111111111111111111111111 success",
+            // Invoke that skips a level
+            "Program FAKE1111111111111111111111111111111111111 invoke [3]",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 20000 of 200000 compute units",
+            // A second "consumed" for the same invocation
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 20000 of 200000 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+            // Program output outside any invocation
+            "Program log: Program FAKE1111111111111111111111111111111111111 invoke [1]",
+        ]));
 
-        assert_eq!(programs.len(), 2);
+        assert_eq!(summary.program_cu.len(), 1);
         assert_eq!(
-            programs.get("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
-            Some(&3158)
+            summary.program_cu["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"],
+            ProgramCu {
+                inclusive: 20000,
+                exclusive: 20000
+            }
         );
+        assert_eq!(summary.top_level_cu(), 20000);
+        assert_eq!(summary.rejected_lines, 5);
+    }
+
+    #[test]
+    fn test_failure_attributed_to_innermost_program() {
+        let summary = summarize_logs(&logs(&[
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 3000 of 180000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: insufficient funds",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 20000 of 200000 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: insufficient funds",
+        ]));
+
         assert_eq!(
-            programs.get("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"),
-            Some(&7913)
+            summary.failed_program.as_deref(),
+            Some("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")
         );
     }
 
     #[test]
-    fn test_sum_multiple_invocations() {
-        let logs = vec![
-            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 7913 of 204938 compute units".to_string(),
-            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 199 of 50825 compute units".to_string(),
-            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 135734 of 179060 compute units".to_string(),
-        ];
+    fn test_anchor_error_log() {
+        let summary = summarize_logs(&logs(&[
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program log: AnchorError thrown in programs/jupiter/src/lib.rs:157. Error Code: SlippageToleranceExceeded. Error Number: 6001. Error Message: Slippage tolerance exceeded.",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 15000 of 200000 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1771",
+        ]));
 
-        let programs = extract_program_cu(&logs);
+        assert_eq!(
+            summary.anchor_error,
+            Some(AnchorErrorLog {
+                program_id: "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+                name: "SlippageToleranceExceeded".to_string(),
+                code: 6001,
+            })
+        );
 
-        // Should sum all three: 7913 + 199 + 135734 = 143846
         assert_eq!(
-            programs.get("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"),
-            Some(&143846)
+            parse_anchor_error("AnchorError caused by account: pool. Error Code: ConstraintMut. Error Number: 2000. Error Message: A mut constraint was violated."),
+            Some(("ConstraintMut", 2000))
+        );
+        assert_eq!(
+            parse_anchor_error("Error: slippage tolerance exceeded"),
+            None
         );
     }
 }
@@ -0,0 +1,315 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::client::RpcClient;
+use super::error::{RetryConfig, RpcError};
+use super::types::BlockData;
+
+/// Weight of the newest sample in the latency / error-rate moving averages
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Consecutive failures after which an endpoint is considered down
+const DOWN_THRESHOLD: u32 = 3;
+
+/// Score penalty (in ms-equivalents) for a 100% error rate
+const ERROR_RATE_PENALTY_MS: f64 = 1_000.0;
+
+/// Score penalty (in ms-equivalents) per slot an endpoint lags the freshest one
+const SLOT_LAG_PENALTY_MS: f64 = 50.0;
+
+/// Score penalty for an endpoint that is down (always sorted last)
+const DOWN_PENALTY_MS: f64 = 1_000_000.0;
+
+/// Timeout for a single health probe request
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Point-in-time health of one endpoint (for display)
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    pub url: String,
+    pub latency_ms: Option<f64>,
+    pub error_rate: f64,
+    pub last_slot: u64,
+    pub slots_behind: u64,
+    pub healthy: bool,
+    pub active: bool,
+}
+
+/// Running health measurements for one endpoint
+#[derive(Debug, Default)]
+struct Health {
+    latency_ewma_ms: Option<f64>,
+    error_rate: f64,
+    consecutive_failures: u32,
+    last_slot: u64,
+}
+
+impl Health {
+    fn record_success(&mut self, latency: Duration) {
+        let ms = latency.as_secs_f64() * 1000.0;
+        self.latency_ewma_ms = Some(match self.latency_ewma_ms {
+            Some(avg) => avg + EWMA_ALPHA * (ms - avg),
+            None => ms,
+        });
+        self.error_rate *= 1.0 - EWMA_ALPHA;
+        self.consecutive_failures = 0;
+    }
+
+    fn record_failure(&mut self) {
+        self.error_rate += EWMA_ALPHA * (1.0 - self.error_rate);
+        self.consecutive_failures += 1;
+    }
+
+    fn is_down(&self) -> bool {
+        self.consecutive_failures >= DOWN_THRESHOLD
+    }
+
+    /// Lower is better
+    fn score(&self, freshest_slot: u64) -> f64 {
+        let latency = self.latency_ewma_ms.unwrap_or(0.0);
+        let lag = freshest_slot.saturating_sub(self.last_slot) as f64;
+        let down = if self.is_down() { DOWN_PENALTY_MS } else { 0.0 };
+
+        latency + self.error_rate * ERROR_RATE_PENALTY_MS + lag * SLOT_LAG_PENALTY_MS + down
+    }
+}
+
+/// One RPC endpoint and its health
+struct Endpoint {
+    url: String,
+    client: RpcClient,
+    probe_client: RpcClient,
+    health: Mutex<Health>,
+}
+
+/// Pool of RPC endpoints with health scoring and automatic failover
+///
+/// Every request goes to the healthiest endpoint first (lowest latency, error
+/// rate and slot lag). If it fails after its own retries, the next healthiest
+/// endpoint is tried, and so on.
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+    active: AtomicUsize,
+}
+
+impl RpcPool {
+    /// Create a pool over the given endpoint URLs
+    pub fn new(urls: Vec<String>, retry_config: RetryConfig) -> Self {
+        assert!(!urls.is_empty(), "RpcPool needs at least one endpoint");
+
+        let endpoints = urls
+            .into_iter()
+            .map(|url| Endpoint {
+                client: RpcClient::with_retry_config(url.clone(), retry_config.clone()),
+                probe_client: RpcClient::with_retry_config(url.clone(), RetryConfig::none()),
+                url,
+                health: Mutex::new(Health::default()),
+            })
+            .collect();
+
+        Self {
+            endpoints,
+            active: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of endpoints in the pool
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    /// Always false (a pool has at least one endpoint)
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    /// Fetch the latest slot from the healthiest endpoint, failing over on error
+    pub async fn get_latest_slot(&self) -> Result<u64, RpcError> {
+        let mut last_err = None;
+
+        for idx in self.ranked() {
+            let start = Instant::now();
+            match self.endpoints[idx].client.get_latest_slot().await {
+                Ok(slot) => {
+                    self.record_success(idx, start.elapsed(), Some(slot));
+                    return Ok(slot);
+                }
+                Err(e) => {
+                    self.record_failure(idx);
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        Err(last_err.expect("pool has at least one endpoint"))
+    }
+
+    /// Fetch a block from the healthiest endpoint, failing over on error
+    pub async fn get_block(&self, slot: u64) -> Result<Option<BlockData>, RpcError> {
+        let mut last_err = None;
+
+        for idx in self.ranked() {
+            let start = Instant::now();
+            match self.endpoints[idx].client.get_block(slot).await {
+                Ok(block) => {
+                    self.record_success(idx, start.elapsed(), None);
+                    return Ok(block);
+                }
+                Err(e) => {
+                    self.record_failure(idx);
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        Err(last_err.expect("pool has at least one endpoint"))
+    }
+
+    /// Query every endpoint's slot once (no retries) to refresh health scores
+    ///
+    /// This is how endpoints that are not currently active get their latency
+    /// and slot freshness measured, and how a down endpoint comes back.
+    pub async fn probe(&self) {
+        for (idx, endpoint) in self.endpoints.iter().enumerate() {
+            let start = Instant::now();
+            match tokio::time::timeout(PROBE_TIMEOUT, endpoint.probe_client.get_latest_slot()).await
+            {
+                // Probes measure health only; `active` is where requests go
+                Ok(Ok(slot)) => self.record_health(idx, start.elapsed(), Some(slot)),
+                _ => self.record_failure(idx),
+            }
+        }
+    }
+
+    /// Snapshot of every endpoint's health, in configuration order
+    pub fn status(&self) -> Vec<EndpointStatus> {
+        let active = self.active.load(Ordering::Relaxed);
+        let freshest = self.freshest_slot();
+
+        self.endpoints
+            .iter()
+            .enumerate()
+            .map(|(idx, endpoint)| {
+                let health = endpoint.health.lock().unwrap();
+                EndpointStatus {
+                    url: endpoint.url.clone(),
+                    latency_ms: health.latency_ewma_ms,
+                    error_rate: health.error_rate,
+                    last_slot: health.last_slot,
+                    slots_behind: freshest.saturating_sub(health.last_slot),
+                    healthy: !health.is_down(),
+                    active: idx == active,
+                }
+            })
+            .collect()
+    }
+
+    /// Endpoint indices ordered from healthiest to least healthy
+    fn ranked(&self) -> Vec<usize> {
+        let freshest = self.freshest_slot();
+        let mut scored: Vec<(usize, f64)> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(idx, e)| (idx, e.health.lock().unwrap().score(freshest)))
+            .collect();
+
+        // Stable sort: ties keep configuration order
+        scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+        scored.into_iter().map(|(idx, _)| idx).collect()
+    }
+
+    fn freshest_slot(&self) -> u64 {
+        self.endpoints
+            .iter()
+            .map(|e| e.health.lock().unwrap().last_slot)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// A routed request succeeded: update health and mark the endpoint active
+    fn record_success(&self, idx: usize, latency: Duration, slot: Option<u64>) {
+        self.record_health(idx, latency, slot);
+        self.active.store(idx, Ordering::Relaxed);
+    }
+
+    /// Update an endpoint's latency, error rate and slot after a successful call
+    fn record_health(&self, idx: usize, latency: Duration, slot: Option<u64>) {
+        let mut health = self.endpoints[idx].health.lock().unwrap();
+        health.record_success(latency);
+        if let Some(slot) = slot {
+            health.last_slot = health.last_slot.max(slot);
+        }
+    }
+
+    fn record_failure(&self, idx: usize) {
+        self.endpoints[idx].health.lock().unwrap().record_failure();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_pool() -> RpcPool {
+        RpcPool::new(
+            vec![
+                "http://a.invalid".to_string(),
+                "http://b.invalid".to_string(),
+                "http://c.invalid".to_string(),
+            ],
+            RetryConfig::none(),
+        )
+    }
+
+    #[test]
+    fn test_prefers_lower_latency() {
+        let pool = test_pool();
+        pool.record_success(0, Duration::from_millis(300), Some(100));
+        pool.record_success(1, Duration::from_millis(40), Some(100));
+        pool.record_success(2, Duration::from_millis(120), Some(100));
+
+        assert_eq!(pool.ranked(), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_failing_endpoint_goes_last() {
+        let pool = test_pool();
+        for idx in 0..3 {
+            pool.record_success(idx, Duration::from_millis(50), Some(100));
+        }
+        for _ in 0..DOWN_THRESHOLD {
+            pool.record_failure(0);
+        }
+
+        assert_eq!(*pool.ranked().last().unwrap(), 0);
+        assert!(!pool.status()[0].healthy);
+
+        // A single success brings it back
+        pool.record_success(0, Duration::from_millis(50), Some(100));
+        assert!(pool.status()[0].healthy);
+    }
+
+    #[test]
+    fn test_stale_endpoint_is_penalized() {
+        let pool = test_pool();
+        pool.record_success(0, Duration::from_millis(20), Some(90));
+        pool.record_success(1, Duration::from_millis(60), Some(100));
+        pool.record_success(2, Duration::from_millis(60), Some(100));
+
+        // 10 slots behind outweighs 40ms of latency
+        assert_eq!(pool.ranked()[2], 0);
+        assert_eq!(pool.status()[0].slots_behind, 10);
+    }
+
+    #[test]
+    fn test_status_tracks_active_endpoint() {
+        let pool = test_pool();
+        pool.record_success(2, Duration::from_millis(10), Some(5));
+
+        let status = pool.status();
+        assert!(status[2].active);
+        assert!(!status[0].active && !status[1].active);
+    }
+}
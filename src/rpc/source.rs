@@ -0,0 +1,69 @@
+use std::future::Future;
+
+use super::client::RpcClient;
+use super::error::RpcError;
+use super::pool::{EndpointStatus, RpcPool};
+use super::types::BlockData;
+
+/// Anything that can tell us the newest slot with a block available
+pub trait SlotSource: Send + Sync + 'static {
+    /// Latest slot that `BlockSource::get_block` can serve
+    fn get_latest_slot(&self) -> impl Future<Output = Result<u64, RpcError>> + Send;
+}
+
+/// Anything that can hand us the block for a slot
+///
+/// Implemented by `RpcClient` and `RpcPool`; tests and alternative backends
+/// (files, other transports) implement it to drive `NetworkMonitor`.
+pub trait BlockSource: Send + Sync + 'static {
+    /// Block for `slot`, or `Ok(None)` if the slot was skipped
+    fn get_block(
+        &self,
+        slot: u64,
+    ) -> impl Future<Output = Result<Option<BlockData>, RpcError>> + Send;
+
+    /// Refresh health measurements (called periodically by the monitor)
+    fn health_check(&self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    /// Health of the underlying endpoints, for display
+    fn endpoint_status(&self) -> Vec<EndpointStatus> {
+        Vec::new()
+    }
+}
+
+impl SlotSource for RpcClient {
+    async fn get_latest_slot(&self) -> Result<u64, RpcError> {
+        RpcClient::get_latest_slot(self).await
+    }
+}
+
+impl BlockSource for RpcClient {
+    async fn get_block(&self, slot: u64) -> Result<Option<BlockData>, RpcError> {
+        RpcClient::get_block(self, slot).await
+    }
+}
+
+impl SlotSource for RpcPool {
+    async fn get_latest_slot(&self) -> Result<u64, RpcError> {
+        RpcPool::get_latest_slot(self).await
+    }
+}
+
+impl BlockSource for RpcPool {
+    async fn get_block(&self, slot: u64) -> Result<Option<BlockData>, RpcError> {
+        RpcPool::get_block(self, slot).await
+    }
+
+    async fn health_check(&self) {
+        // Probing only matters when there is another endpoint to fail over to
+        if self.len() > 1 {
+            self.probe().await;
+        }
+    }
+
+    fn endpoint_status(&self) -> Vec<EndpointStatus> {
+        self.status()
+    }
+}
@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Why a transaction failed (`meta.err`)
+///
+/// Mirrors the runtime's `TransactionError` JSON: unit variants are plain
+/// strings (`"AccountInUse"`), the rest are single-key objects such as
+/// `{"InstructionError": [0, {"Custom": 6001}]}`. Only `InstructionError` is
+/// broken down further; every other variant keeps its name and payload.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(from = "Value", into = "Value")]
+pub enum TransactionError {
+    /// Top-level instruction `index` failed
+    InstructionError { index: u8, error: InstructionError },
+
+    /// Any other variant, e.g. `AccountInUse` or `InsufficientFundsForRent`
+    Other {
+        name: String,
+        details: Option<Value>,
+    },
+}
+
+/// Why an instruction failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstructionError {
+    /// Program-defined error code (`custom program error: 0x...`)
+    Custom(u32),
+
+    /// A runtime error, e.g. `InvalidArgument` or `ComputationalBudgetExceeded`
+    Other {
+        name: String,
+        details: Option<Value>,
+    },
+}
+
+impl TransactionError {
+    /// Index of the top-level instruction that failed, if an instruction failed
+    pub fn instruction_index(&self) -> Option<usize> {
+        match self {
+            TransactionError::InstructionError { index, .. } => Some(*index as usize),
+            TransactionError::Other { .. } => None,
+        }
+    }
+
+    /// Program-defined error code, if an instruction returned one
+    pub fn custom_code(&self) -> Option<u32> {
+        match self {
+            TransactionError::InstructionError {
+                error: InstructionError::Custom(code),
+                ..
+            } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::InstructionError { index, error } => {
+                write!(f, "instruction {}: {}", index, error)
+            }
+            TransactionError::Other { name, .. } => write!(f, "{}", name),
+        }
+    }
+}
+
+impl fmt::Display for InstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstructionError::Custom(code) => write!(f, "custom program error: {:#x}", code),
+            InstructionError::Other { name, .. } => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Split `"Name"` / `{"Name": details}` into its parts
+fn variant(value: &Value) -> Option<(String, Option<Value>)> {
+    match value {
+        Value::String(name) => Some((name.clone(), None)),
+        Value::Object(map) if map.len() == 1 => {
+            let (name, details) = map.iter().next()?;
+            Some((name.clone(), Some(details.clone())))
+        }
+        _ => None,
+    }
+}
+
+fn join_variant(name: String, details: Option<Value>) -> Value {
+    match details {
+        Some(details) => json!({ name: details }),
+        None => Value::String(name),
+    }
+}
+
+impl From<Value> for InstructionError {
+    fn from(value: Value) -> Self {
+        match variant(&value) {
+            Some((name, Some(details))) if name == "Custom" => match details.as_u64() {
+                Some(code) if code <= u32::MAX as u64 => InstructionError::Custom(code as u32),
+                _ => InstructionError::Other {
+                    name,
+                    details: Some(details),
+                },
+            },
+            Some((name, details)) => InstructionError::Other { name, details },
+            None => InstructionError::Other {
+                name: value.to_string(),
+                details: None,
+            },
+        }
+    }
+}
+
+impl From<InstructionError> for Value {
+    fn from(error: InstructionError) -> Self {
+        match error {
+            InstructionError::Custom(code) => json!({ "Custom": code }),
+            InstructionError::Other { name, details } => join_variant(name, details),
+        }
+    }
+}
+
+impl From<Value> for TransactionError {
+    fn from(value: Value) -> Self {
+        // Never fail the whole block over an error shape we don't know
+        let Some((name, details)) = variant(&value) else {
+            return TransactionError::Other {
+                name: value.to_string(),
+                details: None,
+            };
+        };
+
+        if name == "InstructionError" {
+            if let Some([index, error]) = details
+                .as_ref()
+                .and_then(|d| d.as_array())
+                .map(|a| a.as_slice())
+            {
+                if let Some(index) = index.as_u64().and_then(|i| u8::try_from(i).ok()) {
+                    return TransactionError::InstructionError {
+                        index,
+                        error: InstructionError::from(error.clone()),
+                    };
+                }
+            }
+        }
+
+        TransactionError::Other { name, details }
+    }
+}
+
+impl From<TransactionError> for Value {
+    fn from(error: TransactionError) -> Self {
+        match error {
+            TransactionError::InstructionError { index, error } => {
+                json!({ "InstructionError": [index, Value::from(error)] })
+            }
+            TransactionError::Other { name, details } => join_variant(name, details),
+        }
+    }
+}
+
+/// Name of an Anchor framework error code (`anchor_lang::error::ErrorCode`)
+///
+/// Codes from 6000 up are program-specific (`#[error_code]` enums); their names
+/// only show up in the program's own `AnchorError` log line.
+pub fn anchor_error_name(code: u32) -> Option<&'static str> {
+    let name = match code {
+        // Instructions
+        100 => "InstructionMissing",
+        101 => "InstructionFallbackNotFound",
+        102 => "InstructionDidNotDeserialize",
+        103 => "InstructionDidNotSerialize",
+        // IDL instructions
+        1000 => "IdlInstructionStub",
+        1001 => "IdlInstructionInvalidProgram",
+        1002 => "IdlAccountNotEmpty",
+        // Event instructions
+        1500 => "EventInstructionStub",
+        // Constraints
+        2000 => "ConstraintMut",
+        2001 => "ConstraintHasOne",
+        2002 => "ConstraintSigner",
+        2003 => "ConstraintRaw",
+        2004 => "ConstraintOwner",
+        2005 => "ConstraintRentExempt",
+        2006 => "ConstraintSeeds",
+        2007 => "ConstraintExecutable",
+        2008 => "ConstraintState",
+        2009 => "ConstraintAssociated",
+        2010 => "ConstraintAssociatedInit",
+        2011 => "ConstraintClose",
+        2012 => "ConstraintAddress",
+        2013 => "ConstraintZero",
+        2014 => "ConstraintTokenMint",
+        2015 => "ConstraintTokenOwner",
+        2016 => "ConstraintMintMintAuthority",
+        2017 => "ConstraintMintFreezeAuthority",
+        2018 => "ConstraintMintDecimals",
+        2019 => "ConstraintSpace",
+        2020 => "ConstraintAccountIsNone",
+        2021 => "ConstraintTokenTokenProgram",
+        2022 => "ConstraintMintTokenProgram",
+        2023 => "ConstraintAssociatedTokenTokenProgram",
+        // require! family
+        2500 => "RequireViolated",
+        2501 => "RequireEqViolated",
+        2502 => "RequireKeysEqViolated",
+        2503 => "RequireNeqViolated",
+        2504 => "RequireKeysNeqViolated",
+        2505 => "RequireGtViolated",
+        2506 => "RequireGteViolated",
+        // Accounts
+        3000 => "AccountDiscriminatorAlreadySet",
+        3001 => "AccountDiscriminatorNotFound",
+        3002 => "AccountDiscriminatorMismatch",
+        3003 => "AccountDidNotDeserialize",
+        3004 => "AccountDidNotSerialize",
+        3005 => "AccountNotEnoughKeys",
+        3006 => "AccountNotMutable",
+        3007 => "AccountOwnedByWrongProgram",
+        3008 => "InvalidProgramId",
+        3009 => "InvalidProgramExecutable",
+        3010 => "AccountNotSigner",
+        3011 => "AccountNotSystemOwned",
+        3012 => "AccountNotInitialized",
+        3013 => "AccountNotProgramData",
+        3014 => "AccountNotAssociatedTokenAccount",
+        3015 => "AccountSysvarMismatch",
+        3016 => "AccountReallocExceedsLimit",
+        3017 => "AccountDuplicateReallocs",
+        // Miscellaneous
+        4100 => "DeclaredProgramIdMismatch",
+        4101 => "TryingToInitPayerAsProgramAccount",
+        4102 => "InvalidNumericConversion",
+        5000 => "Deprecated",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_instruction_errors() {
+        let custom: TransactionError =
+            serde_json::from_str(r#"{"InstructionError":[2,{"Custom":6001}]}"#).unwrap();
+        assert_eq!(
+            custom,
+            TransactionError::InstructionError {
+                index: 2,
+                error: InstructionError::Custom(6001)
+            }
+        );
+        assert_eq!(custom.instruction_index(), Some(2));
+        assert_eq!(custom.custom_code(), Some(6001));
+        assert_eq!(
+            custom.to_string(),
+            "instruction 2: custom program error: 0x1771"
+        );
+
+        let builtin: TransactionError =
+            serde_json::from_str(r#"{"InstructionError":[0,"ComputationalBudgetExceeded"]}"#)
+                .unwrap();
+        assert_eq!(builtin.custom_code(), None);
+        assert_eq!(
+            builtin.to_string(),
+            "instruction 0: ComputationalBudgetExceeded"
+        );
+    }
+
+    #[test]
+    fn test_parse_other_errors() {
+        let unit: TransactionError = serde_json::from_str(r#""AccountInUse""#).unwrap();
+        assert_eq!(unit.instruction_index(), None);
+        assert_eq!(unit.to_string(), "AccountInUse");
+
+        let with_details: TransactionError =
+            serde_json::from_str(r#"{"InsufficientFundsForRent":{"account_index":2}}"#).unwrap();
+        assert_eq!(with_details.to_string(), "InsufficientFundsForRent");
+
+        // Unknown shapes are kept, not rejected
+        let odd: TransactionError = serde_json::from_str("42").unwrap();
+        assert_eq!(odd.to_string(), "42");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for text in [
+            r#"{"InstructionError":[2,{"Custom":6001}]}"#,
+            r#"{"InstructionError":[0,"InvalidArgument"]}"#,
+            r#"{"InstructionError":[1,{"BorshIoError":"Unknown"}]}"#,
+            r#""AccountInUse""#,
+            r#"{"DuplicateInstruction":3}"#,
+        ] {
+            let error: TransactionError = serde_json::from_str(text).unwrap();
+            assert_eq!(serde_json::to_string(&error).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn test_anchor_error_names() {
+        assert_eq!(anchor_error_name(2003), Some("ConstraintRaw"));
+        assert_eq!(anchor_error_name(3012), Some("AccountNotInitialized"));
+        assert_eq!(anchor_error_name(6001), None);
+    }
+}
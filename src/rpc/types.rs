@@ -1,13 +1,26 @@
 use serde::{Deserialize, Serialize};
 
+use super::transaction_error::TransactionError;
+
 /// Generic JSON-RPC response wrapper
+///
+/// Exactly one of `result` and `error` is set by a well-behaved endpoint.
+/// A `null` result (e.g. getBlock on an empty slot) deserializes to `None`.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RpcResponse<T> {
     pub jsonrpc: String,
-    pub result: T,
+    pub result: Option<T>,
+    pub error: Option<RpcErrorObject>,
     pub id: String,
 }
 
+/// JSON-RPC error object (`{"code": -32007, "message": "..."}`)
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RpcErrorObject {
+    pub code: i64,
+    pub message: String,
+}
+
 /// Response for getSlot method
 pub type SlotResponse = RpcResponse<u64>;
 
@@ -16,14 +29,147 @@ pub type SlotResponse = RpcResponse<u64>;
 pub struct TransactionData {
     pub meta: Option<TransactionMeta>,
     pub transaction: Transaction,
+
+    /// "legacy" or 0 (absent when the request didn't set `maxSupportedTransactionVersion`)
+    #[serde(default)]
+    pub version: Option<TransactionVersion>,
+}
+
+/// Transaction message format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum TransactionVersion {
+    Legacy(Legacy),
+    Number(u8),
+}
+
+/// The `"legacy"` version tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Legacy {
+    Legacy,
+}
+
+impl TransactionData {
+    /// Legacy unless the node reported a numbered version
+    pub fn is_versioned(&self) -> bool {
+        matches!(self.version, Some(TransactionVersion::Number(_)))
+    }
+
+    /// Full account key list: static keys, then lookup-table addresses
+    pub fn account_keys(&self) -> AccountKeys<'_> {
+        AccountKeys {
+            static_keys: &self.transaction.message.account_keys,
+            loaded: self
+                .meta
+                .as_ref()
+                .and_then(|meta| meta.loaded_addresses.as_ref()),
+        }
+    }
+
+    /// Program ID of each inner (CPI) instruction, in order
+    pub fn inner_instruction_programs(&self) -> Vec<String> {
+        let account_keys = self.account_keys();
+        self.meta
+            .iter()
+            .flat_map(|meta| meta.inner_instructions.iter().flatten())
+            .flat_map(|inner| &inner.instructions)
+            .filter_map(|ix| account_keys.get(ix.program_id_index as usize))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// A transaction's account keys, resolving address lookup tables
+///
+/// Instruction account indices run over the static keys first, then the
+/// writable and finally the readonly addresses loaded from lookup tables
+/// (`meta.loadedAddresses`, v0 transactions only).
+#[derive(Debug, Clone, Copy)]
+pub struct AccountKeys<'a> {
+    static_keys: &'a [String],
+    loaded: Option<&'a LoadedAddresses>,
+}
+
+impl<'a> AccountKeys<'a> {
+    /// Key at `index`, or None if it's out of range
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        if let Some(key) = self.static_keys.get(index) {
+            return Some(key);
+        }
+
+        let loaded = self.loaded?;
+        let index = index - self.static_keys.len();
+        match loaded.writable.get(index) {
+            Some(key) => Some(key),
+            None => loaded.readonly.get(index - loaded.writable.len()),
+        }
+        .map(String::as_str)
+    }
+
+    /// Total number of keys
+    pub fn len(&self) -> usize {
+        self.static_keys.len()
+            + self
+                .loaded
+                .map_or(0, |loaded| loaded.writable.len() + loaded.readonly.len())
+    }
+
+    /// True if the transaction has no account keys
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All keys in index order
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + 'a {
+        let loaded = self.loaded.into_iter();
+        self.static_keys
+            .iter()
+            .chain(loaded.clone().flat_map(|loaded| &loaded.writable))
+            .chain(loaded.flat_map(|loaded| &loaded.readonly))
+            .map(String::as_str)
+    }
 }
 
 /// Transaction metadata (includes logs and status)
 #[derive(Debug, Deserialize, Serialize)]
 pub struct TransactionMeta {
-    pub err: Option<serde_json::Value>, // null if success, error details if failed
+    pub err: Option<TransactionError>, // null if success, error details if failed
     #[serde(rename = "logMessages", default)]
     pub log_messages: Option<Vec<String>>,
+
+    /// Total CU consumed by the transaction (absent on very old blocks)
+    #[serde(rename = "computeUnitsConsumed", default)]
+    pub compute_units_consumed: Option<u64>,
+
+    /// Fee paid in lamports
+    #[serde(default)]
+    pub fee: u64,
+
+    /// Accounts loaded from address lookup tables (v0 transactions only)
+    #[serde(rename = "loadedAddresses", default)]
+    pub loaded_addresses: Option<LoadedAddresses>,
+
+    /// CPI instructions, grouped by the top-level instruction that issued them
+    #[serde(rename = "innerInstructions", default)]
+    pub inner_instructions: Option<Vec<InnerInstructions>>,
+}
+
+/// Addresses loaded from lookup tables
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LoadedAddresses {
+    #[serde(default)]
+    pub writable: Vec<String>,
+    #[serde(default)]
+    pub readonly: Vec<String>,
+}
+
+/// Inner (CPI) instructions of one top-level instruction
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InnerInstructions {
+    /// Index of the top-level instruction
+    pub index: u8,
+    pub instructions: Vec<Instruction>,
 }
 
 /// Transaction details
@@ -36,6 +182,10 @@ pub struct Transaction {
 pub struct Instruction {
     #[serde(rename = "programIdIndex")]
     pub program_id_index: u8,
+
+    /// CPI depth (inner instructions only, 2 = called by a top-level instruction)
+    #[serde(rename = "stackHeight", default)]
+    pub stack_height: Option<u32>,
     // Note: There are other fields (accounts, data) but we don't need them yet
 }
 
@@ -47,13 +197,58 @@ pub struct Message {
     pub instructions: Vec<Instruction>,
 }
 
+impl Message {
+    /// Program ID of each top-level instruction, in order
+    ///
+    /// Program IDs are always static keys (lookup tables can't load them).
+    pub fn instruction_programs(&self) -> Vec<String> {
+        self.instructions
+            .iter()
+            .filter_map(|ix| self.account_keys.get(ix.program_id_index as usize))
+            .cloned()
+            .collect()
+    }
+}
+
 /// Block data response
 #[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct BlockData {
+    /// Estimated production time, as Unix seconds (None if not available)
+    #[serde(default)]
+    pub block_time: Option<i64>,
+
+    /// Number of blocks beneath this one (None on very old ledgers)
+    #[serde(default)]
+    pub block_height: Option<u64>,
+
+    /// Slot of the parent block (skipped slots in between are not counted)
+    #[serde(default)]
+    pub parent_slot: u64,
+
     pub transactions: Vec<TransactionData>,
 }
 
-pub type BlockResponse = RpcResponse<Option<BlockData>>;
+/// PubSub notification envelope (`{"method": "...", "params": {...}}`)
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WsNotification {
+    pub method: String,
+    pub params: WsNotificationParams,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WsNotificationParams {
+    pub result: serde_json::Value,
+    pub subscription: u64,
+}
+
+/// Payload of a slotNotification
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SlotInfo {
+    pub parent: u64,
+    pub root: u64,
+    pub slot: u64,
+}
 
 /// Log message extracted from transaction
 #[derive(Debug, Clone)]
@@ -61,3 +256,89 @@ pub struct LogMessage {
     pub program_id: String,
     pub message: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_keys_resolve_lookup_tables() {
+        let tx: TransactionData = serde_json::from_value(serde_json::json!({
+            "version": 0,
+            "meta": {
+                "err": null,
+                "loadedAddresses": {
+                    "writable": ["Pool111111111111111111111111111111111111111"],
+                    "readonly": ["whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"]
+                },
+                "innerInstructions": [
+                    {"index": 0, "instructions": [{"programIdIndex": 3, "stackHeight": 2}]}
+                ]
+            },
+            "transaction": {
+                "message": {
+                    "accountKeys": [
+                        "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU",
+                        "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"
+                    ],
+                    "instructions": [{"programIdIndex": 1}]
+                }
+            }
+        }))
+        .unwrap();
+
+        assert!(tx.is_versioned());
+
+        let keys = tx.account_keys();
+        assert_eq!(keys.len(), 4);
+        assert_eq!(
+            keys.get(1),
+            Some("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4")
+        );
+        assert_eq!(
+            keys.get(2),
+            Some("Pool111111111111111111111111111111111111111")
+        );
+        assert_eq!(
+            keys.get(3),
+            Some("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc")
+        );
+        assert_eq!(keys.get(4), None);
+
+        // A CPI into a program loaded from a lookup table
+        assert_eq!(
+            tx.inner_instruction_programs(),
+            vec!["whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_transaction_version() {
+        let legacy: Option<TransactionVersion> = serde_json::from_str(r#""legacy""#).unwrap();
+        let v0: Option<TransactionVersion> = serde_json::from_str("0").unwrap();
+
+        assert_eq!(legacy, Some(TransactionVersion::Legacy(Legacy::Legacy)));
+        assert_eq!(v0, Some(TransactionVersion::Number(0)));
+    }
+
+    #[test]
+    fn test_block_chain_time() {
+        let block: BlockData = serde_json::from_value(serde_json::json!({
+            "blockTime": 1_700_000_000,
+            "blockHeight": 900,
+            "parentSlot": 999,
+            "transactions": []
+        }))
+        .unwrap();
+        assert_eq!(block.block_time, Some(1_700_000_000));
+        assert_eq!(block.block_height, Some(900));
+        assert_eq!(block.parent_slot, 999);
+
+        // blockTime is null when the validator doesn't know it
+        let block: BlockData =
+            serde_json::from_value(serde_json::json!({ "blockTime": null, "transactions": [] }))
+                .unwrap();
+        assert_eq!(block.block_time, None);
+        assert_eq!(block.block_height, None);
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::error::RetryConfig;
+use super::types::{SlotInfo, WsNotification};
+
+/// Derive the PubSub URL from an HTTP RPC URL
+///
+/// `https://` becomes `wss://` and `http://` becomes `ws://`. An explicit port
+/// is bumped by one, matching the validator's default layout (8899 -> 8900).
+pub fn ws_url_from_http(http_url: &str) -> String {
+    let (scheme, rest) = match http_url.split_once("://") {
+        Some(("https", rest)) => ("wss", rest),
+        Some(("http", rest)) => ("ws", rest),
+        Some((other, rest)) => (other, rest),
+        None => ("wss", http_url),
+    };
+
+    // Split "host:port/path?query" into authority and the remainder
+    let split_at = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (authority, tail) = rest.split_at(split_at);
+
+    let authority = match authority.rsplit_once(':') {
+        Some((host, port)) => match port.parse::<u16>() {
+            Ok(port) => format!("{}:{}", host, port.saturating_add(1)),
+            Err(_) => authority.to_string(),
+        },
+        None => authority.to_string(),
+    };
+
+    format!("{}://{}{}", scheme, authority, tail)
+}
+
+/// Live root-slot feed from `slotSubscribe` / `rootSubscribe`
+///
+/// A background task keeps the WebSocket connected, reconnecting with backoff
+/// when it drops. Both subscriptions are opened so the feed keeps working on
+/// endpoints that only support one of them; each reports the latest rooted
+/// slot, which is the newest slot `getBlock` can serve.
+pub struct SlotSubscription {
+    rx: watch::Receiver<Option<u64>>,
+    task: JoinHandle<()>,
+}
+
+impl SlotSubscription {
+    /// Connect to `ws_url` in the background
+    pub fn spawn(ws_url: String, retry_config: RetryConfig) -> Self {
+        let (tx, rx) = watch::channel(None);
+        let task = tokio::spawn(Self::run(ws_url, tx, retry_config));
+        Self { rx, task }
+    }
+
+    /// Latest rooted slot, or `None` while disconnected
+    pub fn latest(&self) -> Option<u64> {
+        *self.rx.borrow()
+    }
+
+    /// Wait up to `timeout` for a new root
+    ///
+    /// Returns `None` if the connection is down or stalled, in which case the
+    /// caller should fall back to polling.
+    pub async fn next_root(&mut self, timeout: Duration) -> Option<u64> {
+        match tokio::time::timeout(timeout, self.rx.changed()).await {
+            Ok(Ok(())) => *self.rx.borrow_and_update(),
+            _ => None,
+        }
+    }
+
+    /// Connection loop: connect, stream, reconnect with backoff
+    async fn run(ws_url: String, tx: watch::Sender<Option<u64>>, retry_config: RetryConfig) {
+        let mut attempt = 0;
+
+        while !tx.is_closed() {
+            let mut received = false;
+            let _ = Self::stream_roots(&ws_url, &tx, &mut received).await;
+
+            // Disconnected: let consumers know so they can poll instead
+            tx.send_replace(None);
+
+            if received {
+                attempt = 0;
+            }
+            tokio::time::sleep(retry_config.backoff(attempt)).await;
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    /// Single connection: subscribe and forward roots until the socket closes
+    async fn stream_roots(
+        ws_url: &str,
+        tx: &watch::Sender<Option<u64>>,
+        received: &mut bool,
+    ) -> Result<()> {
+        let (mut socket, _) = tokio_tungstenite::connect_async(ws_url)
+            .await
+            .context("Failed to connect WebSocket")?;
+
+        for (id, method) in [(1, "slotSubscribe"), (2, "rootSubscribe")] {
+            let request = json!({"jsonrpc": "2.0", "id": id, "method": method});
+            socket.send(Message::Text(request.to_string())).await?;
+        }
+
+        while let Some(message) = socket.next().await {
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+
+            if let Some(root) = parse_root(&text) {
+                *received = true;
+                // Roots only move forward; ignore a lagging subscription
+                tx.send_if_modified(|latest| match *latest {
+                    Some(current) if current >= root => false,
+                    _ => {
+                        *latest = Some(root);
+                        true
+                    }
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SlotSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Extract the rooted slot from a PubSub notification
+///
+/// Subscription confirmations and unknown methods yield `None`.
+fn parse_root(text: &str) -> Option<u64> {
+    let notification: WsNotification = serde_json::from_str(text).ok()?;
+
+    match notification.method.as_str() {
+        "slotNotification" => serde_json::from_value::<SlotInfo>(notification.params.result)
+            .ok()
+            .map(|info| info.root),
+        "rootNotification" => notification.params.result.as_u64(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_ws_url_from_http() {
+        assert_eq!(
+            ws_url_from_http("https://api.mainnet-beta.solana.com"),
+            "wss://api.mainnet-beta.solana.com"
+        );
+        assert_eq!(
+            ws_url_from_http("http://127.0.0.1:8899"),
+            "ws://127.0.0.1:8900"
+        );
+        assert_eq!(
+            ws_url_from_http("https://rpc.example.com/?api-key=abc"),
+            "wss://rpc.example.com/?api-key=abc"
+        );
+    }
+
+    #[test]
+    fn test_parse_root() {
+        let slot = r#"{"jsonrpc":"2.0","method":"slotNotification","params":{"result":{"parent":75,"root":44,"slot":76},"subscription":0}}"#;
+        let root = r#"{"jsonrpc":"2.0","method":"rootNotification","params":{"result":42,"subscription":1}}"#;
+        let confirmation = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+
+        assert_eq!(parse_root(slot), Some(44));
+        assert_eq!(parse_root(root), Some(42));
+        assert_eq!(parse_root(confirmation), None);
+    }
+
+    type ServerSocket = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+
+    /// Accept one WebSocket client and confirm its two subscriptions
+    async fn accept_subscriber(listener: &TcpListener) -> ServerSocket {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        for id in 1..=2 {
+            socket.next().await.unwrap().unwrap();
+            let confirmation = json!({"jsonrpc": "2.0", "result": id, "id": id});
+            socket
+                .send(Message::Text(confirmation.to_string()))
+                .await
+                .unwrap();
+        }
+
+        socket
+    }
+
+    async fn send_root(socket: &mut ServerSocket, root: u64) {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "rootNotification",
+            "params": {"result": root, "subscription": 2}
+        });
+        socket
+            .send(Message::Text(notification.to_string()))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscription_streams_and_reconnects() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let retry = RetryConfig {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(20),
+            ..RetryConfig::default()
+        };
+        let mut subscription = SlotSubscription::spawn(url, retry);
+        let timeout = Duration::from_secs(2);
+
+        let mut socket = accept_subscriber(&listener).await;
+        send_root(&mut socket, 100).await;
+        assert_eq!(subscription.next_root(timeout).await, Some(100));
+        send_root(&mut socket, 101).await;
+        assert_eq!(subscription.next_root(timeout).await, Some(101));
+
+        // A lagging (older) root is ignored
+        send_root(&mut socket, 99).await;
+        send_root(&mut socket, 102).await;
+        assert_eq!(subscription.next_root(timeout).await, Some(102));
+
+        // Server goes away: the feed reports "disconnected"...
+        socket.close(None).await.unwrap();
+        assert_eq!(subscription.next_root(timeout).await, None);
+        assert_eq!(subscription.latest(), None);
+
+        // ...and picks up again once the client has reconnected
+        let mut socket = accept_subscriber(&listener).await;
+        send_root(&mut socket, 103).await;
+        assert_eq!(subscription.next_root(timeout).await, Some(103));
+    }
+}
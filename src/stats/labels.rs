@@ -0,0 +1,77 @@
+/// Human-readable names of well-known programs
+pub const KNOWN_PROGRAMS: &[(&str, &str)] = &[
+    ("11111111111111111111111111111111", "System Program"),
+    ("Vote111111111111111111111111111111111111111", "Vote"),
+    ("Stake11111111111111111111111111111111111111", "Stake"),
+    (
+        "ComputeBudget111111111111111111111111111111",
+        "Compute Budget",
+    ),
+    (
+        "AddressLookupTab1e1111111111111111111111111",
+        "Address Lookup Table",
+    ),
+    (
+        "BPFLoaderUpgradeab1e11111111111111111111111",
+        "BPF Loader Upgradeable",
+    ),
+    (
+        "Ed25519SigVerify111111111111111111111111111",
+        "Ed25519 SigVerify",
+    ),
+    (
+        "KeccakSecp256k11111111111111111111111111111",
+        "Secp256k1 SigVerify",
+    ),
+    ("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", "Token"),
+    ("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb", "Token-2022"),
+    (
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+        "Associated Token Account",
+    ),
+    ("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr", "Memo"),
+    ("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4", "Jupiter v6"),
+    (
+        "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+        "Raydium AMM v4",
+    ),
+    (
+        "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK",
+        "Raydium CLMM",
+    ),
+    (
+        "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc",
+        "Orca Whirlpool",
+    ),
+    (
+        "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo",
+        "Meteora DLMM",
+    ),
+    ("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY", "Phoenix"),
+    ("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M3uBBbF6P", "Pump.fun"),
+];
+
+/// Name of a well-known program, if we have one
+pub fn program_label(program_id: &str) -> Option<&'static str> {
+    KNOWN_PROGRAMS
+        .iter()
+        .find(|(id, _)| *id == program_id)
+        .map(|(_, label)| *label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_label() {
+        assert_eq!(
+            program_label("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"),
+            Some("Jupiter v6")
+        );
+        assert_eq!(
+            program_label("Unknown111111111111111111111111111111111111"),
+            None
+        );
+    }
+}
@@ -1,12 +1,17 @@
 mod filter;
+mod labels;
 mod monitor;
 mod network;
 mod program;
 mod ring_buffer;
+mod rollup;
+mod sketch;
 
 // Re-export RingBuffer so users can do: use soltop::stats::RingBuffer;
 pub use filter::is_system_program;
+pub use labels::program_label;
 pub use monitor::{MonitorConfig, NetworkMonitor};
 pub use network::NetworkState;
-pub use program::ProgramStats;
+pub use program::{ChainTime, CuMode, HistoryPoint, ProgramStats, SlotStats, StatsView};
 pub use ring_buffer::RingBuffer;
+pub use sketch::CuSketch;
@@ -1,45 +1,93 @@
 use anyhow::Result;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinSet;
 
 use super::network::NetworkState;
-use crate::rpc::RpcClient;
+use crate::rpc::{
+    ws_url_from_http, BlockData, BlockSource, RetryConfig, RpcPool, SlotSource, SlotSubscription,
+};
+
+/// How often the block source is asked to refresh endpoint health
+const HEALTH_PROBE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long to wait for a WebSocket root before double-checking with getSlot
+const WS_STALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How many fetched-but-uncommitted slots may pile up behind a slow one,
+/// as a multiple of the fetch concurrency
+const REORDER_WINDOW_FACTOR: usize = 4;
+
+/// Longest wait between getSlot attempts while the tip keeps failing
+const MAX_TIP_BACKOFF: Duration = Duration::from_secs(10);
 
 /// Configuration for the network monitor
 pub struct MonitorConfig {
-    pub rpc_url: String,
-    pub window_duration: Duration,
-    pub buffer_capacity: usize,
+    pub rpc_urls: Vec<String>, // One or more endpoints, pooled with failover
+    pub window_duration: Duration, // Slots older than this are evicted
+    pub buffer_capacity: usize, // Hard cap on slots kept per program
     pub poll_interval: Duration, // How often to fetch new slots
+    pub retry_config: RetryConfig, // Backoff policy for failed RPC calls
+    pub websocket: bool,       // Push new slots via slotSubscribe/rootSubscribe
+    pub ws_url: Option<String>, // Explicit PubSub URL (derived from rpc_urls if None)
+    pub fetch_concurrency: usize, // getBlock requests in flight at once
+    pub count_inner_instructions: bool, // Count CPI invocations, not just top-level ones
+    pub max_programs: usize,   // Most programs tracked; least active dropped beyond
+    pub verbose: bool,         // Collect performance stats while processing blocks
 }
 
 impl Default for MonitorConfig {
     fn default() -> Self {
         Self {
-            rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
+            rpc_urls: vec!["https://api.mainnet-beta.solana.com".to_string()],
             window_duration: Duration::from_secs(5 * 60), // 5 minutes
             buffer_capacity: 750,                         // ~5 minutes at 400ms/slot
             poll_interval: Duration::from_millis(400),    // Match slot time
+            retry_config: RetryConfig::default(),
+            websocket: false,
+            ws_url: None,
+            fetch_concurrency: 4,
+            count_inner_instructions: false,
+            max_programs: 5_000,
+            verbose: false,
         }
     }
 }
 
 /// Main network monitoring coordinator
-pub struct NetworkMonitor {
+///
+/// Generic over where slots and blocks come from; by default an `RpcPool`
+/// built from `MonitorConfig::rpc_urls`.
+pub struct NetworkMonitor<S = RpcPool> {
     config: MonitorConfig,
     state: Arc<RwLock<NetworkState>>,
+    source: Arc<S>,
 }
 
 impl NetworkMonitor {
-    /// Create a new network monitor
+    /// Create a new network monitor backed by the configured RPC endpoints
     pub fn new(config: MonitorConfig) -> Self {
-        let state = Arc::new(RwLock::new(NetworkState::new(
-            config.window_duration,
-            config.buffer_capacity,
-        )));
+        let source = RpcPool::new(config.rpc_urls.clone(), config.retry_config.clone());
+        Self::with_source(config, source)
+    }
+}
 
-        Self { config, state }
+impl<S: SlotSource + BlockSource> NetworkMonitor<S> {
+    /// Create a new network monitor fed by a custom slot/block source
+    pub fn with_source(config: MonitorConfig, source: S) -> Self {
+        let mut state = NetworkState::new(config.window_duration, config.buffer_capacity);
+        state.update_fetch_status(0, config.fetch_concurrency);
+        state.set_count_inner_instructions(config.count_inner_instructions);
+        state.set_max_programs(config.max_programs);
+        let state = Arc::new(RwLock::new(state));
+
+        Self {
+            config,
+            state,
+            source: Arc::new(source),
+        }
     }
 
     /// Get a clone of the shared state (for consumers to access)
@@ -48,61 +96,149 @@ impl NetworkMonitor {
     }
 
     /// Producer task: continuously fetch slots and send to channel
+    ///
+    /// While catching up, slots are sent back to back without asking for the
+    /// tip again. Once caught up, the next tip comes from the WebSocket feed if
+    /// one is connected, otherwise from polling getSlot.
     async fn produce_slots(
-        rpc_client: RpcClient,
+        source: Arc<S>,
+        mut slot_feed: Option<SlotSubscription>,
         poll_interval: Duration,
         tx: mpsc::Sender<u64>,
         state: Arc<RwLock<NetworkState>>,
     ) -> Result<()> {
-        let mut current_slot = rpc_client.get_latest_slot().await?;
+        let mut current_slot = Self::fetch_tip(&source, poll_interval).await;
+        let mut latest_slot = current_slot;
 
         loop {
-            // Check where we are
-            let latest_slot = rpc_client.get_latest_slot().await?;
-
-            // Update the latest network slot in state for UI display
+            // Update the latest network slot and endpoint health in state for UI display
             {
                 let mut state = state.write().await;
                 state.update_latest_network_slot(latest_slot);
+                state.update_endpoint_status(source.endpoint_status());
+                state.update_websocket_status(slot_feed.as_ref().map(|f| f.latest().is_some()));
             }
 
-            if current_slot <= latest_slot {
-                // Send slot immediately
+            // Send every slot up to the known tip immediately
+            // No sleep or getSlot when catching up!
+            while current_slot <= latest_slot {
                 tx.send(current_slot).await?;
                 current_slot += 1;
-                // No sleep when catching up!
-            } else {
-                // We're ahead, wait a bit
-                tokio::time::sleep(poll_interval).await;
+            }
+
+            // Caught up: wait for the tip to move
+            let pushed = match slot_feed.as_mut() {
+                Some(feed) if feed.latest().is_some() => feed.next_root(WS_STALL_TIMEOUT).await,
+                _ => None,
+            };
+
+            let tip = match pushed {
+                Some(root) => root,
+                None => {
+                    // No WebSocket (or it is down/stalled): poll
+                    tokio::time::sleep(poll_interval).await;
+                    Self::fetch_tip(&source, poll_interval).await
+                }
+            };
+
+            latest_slot = latest_slot.max(tip);
+        }
+    }
+
+    /// Ask for the tip until it answers, backing off between failures
+    ///
+    /// An error that outlives the source's own retries is logged rather than
+    /// ending the pipeline: a pool can fail over to another endpoint by the
+    /// next attempt.
+    async fn fetch_tip(source: &S, poll_interval: Duration) -> u64 {
+        let mut backoff = poll_interval;
+        loop {
+            match source.get_latest_slot().await {
+                Ok(slot) => return slot,
+                Err(e) => {
+                    eprintln!("Failed to fetch the latest slot: {}", e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_TIP_BACKOFF);
+                }
             }
         }
     }
 
-    /// Consumer task: receive slots from channel and update state
+    /// Consumer task: fetch blocks concurrently and commit them to state in slot order
+    ///
+    /// Up to `concurrency` getBlock requests run at once and may finish in any
+    /// order; finished blocks wait in a `CommitQueue` until every earlier slot
+    /// has been committed, so `process_block` always sees slots in order.
     async fn consume_slots(
         state: Arc<RwLock<NetworkState>>,
-        rpc_client: RpcClient,
+        source: Arc<S>,
         mut rx: mpsc::Receiver<u64>,
+        concurrency: usize,
+        verbose: bool,
     ) -> Result<()> {
-        while let Some(slot) = rx.recv().await {
-            match rpc_client.get_block(slot).await {
-                Ok(Some(block_response)) if block_response.result.is_some() => {
-                    // Happy path: block exists and has data
-                    let block_data = block_response.result.unwrap();
-
-                    {
-                        // Explicit scope for lock
-                        let mut state = state.write().await;
-                        state.process_block(slot, &block_data, false);
-                    } // Lock dropped here
-                }
-                Ok(_) => {
-                    // Block skipped or no data
-                }
-                Err(e) => {
-                    // Network error - log but continue
-                    eprintln!("Error fetching slot {}: {}", slot, e);
-                }
+        let concurrency = concurrency.max(1);
+        let reorder_window = concurrency * REORDER_WINDOW_FACTOR;
+
+        let mut in_flight = JoinSet::new();
+        // Slot each fetch task is for, so a panicked task can still be committed past
+        let mut task_slots = HashMap::new();
+        let mut queue = CommitQueue::new();
+        let mut channel_open = true;
+
+        while channel_open || !in_flight.is_empty() {
+            let can_dispatch =
+                channel_open && in_flight.len() < concurrency && queue.pending() < reorder_window;
+
+            tokio::select! {
+                slot = rx.recv(), if can_dispatch => match slot {
+                    Some(slot) => {
+                        queue.dispatch(slot);
+                        let source = Arc::clone(&source);
+                        let task = in_flight.spawn(async move { source.get_block(slot).await });
+                        task_slots.insert(task.id(), slot);
+
+                        state
+                            .write()
+                            .await
+                            .update_fetch_status(in_flight.len(), concurrency);
+                    }
+                    None => channel_open = false,
+                },
+                Some(joined) = in_flight.join_next_with_id() => {
+                    let id = match &joined {
+                        Ok((id, _)) => *id,
+                        Err(e) => e.id(),
+                    };
+                    // Every spawned task is recorded before it can complete
+                    let slot = task_slots.remove(&id).expect("fetch task has a slot");
+
+                    let block = match joined {
+                        // Happy path: block exists and has data
+                        Ok((_, Ok(Some(block_data)))) => Some(block_data),
+                        // Slot skipped by the leader, nothing to record
+                        Ok((_, Ok(None))) => None,
+                        Ok((_, Err(e))) => {
+                            // Retries exhausted or non-retryable error - log but continue
+                            eprintln!("Error fetching slot {}: {}", slot, e);
+                            None
+                        }
+                        Err(e) => {
+                            // The fetch task panicked - skip its slot rather than stop
+                            eprintln!("Fetch task for slot {} failed: {}", slot, e);
+                            None
+                        }
+                    };
+                    queue.complete(slot, block);
+
+                    // Explicit scope for lock
+                    let mut state = state.write().await;
+                    while let Some((slot, block)) = queue.pop_ready() {
+                        if let Some(block_data) = block {
+                            state.process_block(slot, &block_data, verbose);
+                        }
+                    }
+                    state.update_fetch_status(in_flight.len(), concurrency);
+                } // Lock dropped here
             }
         }
 
@@ -110,6 +246,19 @@ impl NetworkMonitor {
         Ok(())
     }
 
+    /// Health task: periodically probe every endpoint so idle ones get scored too
+    async fn probe_endpoints(source: Arc<S>, state: Arc<RwLock<NetworkState>>) {
+        let mut interval = tokio::time::interval(HEALTH_PROBE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            source.health_check().await;
+
+            let mut state = state.write().await;
+            state.update_endpoint_status(source.endpoint_status());
+        }
+    }
+
     /// Start the monitoring pipeline
     /// This function runs forever (until Ctrl+C)
     pub async fn start(&self) -> Result<()> {
@@ -117,17 +266,33 @@ impl NetworkMonitor {
 
         // Clone data for consumer
         let consumer_state = Arc::clone(&self.state);
-        let rpc_client = RpcClient::new(self.config.rpc_url.clone());
+        let consumer_source = Arc::clone(&self.source);
+        let concurrency = self.config.fetch_concurrency;
+        let verbose = self.config.verbose;
 
         // Clone data for producer
-        let producer_client = RpcClient::new(self.config.rpc_url.clone());
+        let producer_source = Arc::clone(&self.source);
         let producer_state = Arc::clone(&self.state);
         let poll_interval = self.config.poll_interval;
+        let slot_feed = self.config.websocket.then(|| {
+            let ws_url = self
+                .config
+                .ws_url
+                .clone()
+                .unwrap_or_else(|| ws_url_from_http(&self.config.rpc_urls[0]));
+            SlotSubscription::spawn(ws_url, self.config.retry_config.clone())
+        });
 
         // Spawn producer
         let producer = tokio::spawn(async move {
-            if let Err(e) =
-                Self::produce_slots(producer_client, poll_interval, tx, producer_state).await
+            if let Err(e) = Self::produce_slots(
+                producer_source,
+                slot_feed,
+                poll_interval,
+                tx,
+                producer_state,
+            )
+            .await
             {
                 eprintln!("Producer error: {}", e);
             }
@@ -135,12 +300,283 @@ impl NetworkMonitor {
 
         // Spawn consumer
         let consumer = tokio::spawn(async move {
-            if let Err(e) = Self::consume_slots(consumer_state, rpc_client, rx).await {
+            if let Err(e) =
+                Self::consume_slots(consumer_state, consumer_source, rx, concurrency, verbose).await
+            {
                 eprintln!("Consumer error: {}", e);
             }
         });
 
+        // Spawn health probe
+        let prober = tokio::spawn(Self::probe_endpoints(
+            Arc::clone(&self.source),
+            Arc::clone(&self.state),
+        ));
+
         let _ = tokio::join!(producer, consumer);
+        prober.abort();
         Ok(())
     }
 }
+
+/// Reorder buffer between out-of-order fetches and in-order commits
+///
+/// Slots are dispatched in increasing order; `pop_ready` only yields a slot
+/// once it and every slot dispatched before it have completed.
+struct CommitQueue {
+    dispatched: VecDeque<u64>,
+    completed: HashMap<u64, Option<BlockData>>,
+}
+
+impl CommitQueue {
+    fn new() -> Self {
+        Self {
+            dispatched: VecDeque::new(),
+            completed: HashMap::new(),
+        }
+    }
+
+    /// Slots dispatched but not yet committed
+    fn pending(&self) -> usize {
+        self.dispatched.len()
+    }
+
+    fn dispatch(&mut self, slot: u64) {
+        self.dispatched.push_back(slot);
+    }
+
+    fn complete(&mut self, slot: u64, block: Option<BlockData>) {
+        self.completed.insert(slot, block);
+    }
+
+    /// Next slot ready to commit, in dispatch order
+    fn pop_ready(&mut self) -> Option<(u64, Option<BlockData>)> {
+        let next = *self.dispatched.front()?;
+        let block = self.completed.remove(&next)?;
+        self.dispatched.pop_front();
+        Some((next, block))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::RpcError;
+    use serde_json::json;
+
+    fn block() -> Option<BlockData> {
+        Some(BlockData {
+            block_time: None,
+            block_height: None,
+            parent_slot: 0,
+            transactions: vec![],
+        })
+    }
+
+    #[test]
+    fn test_commit_queue_orders_out_of_order_completions() {
+        let mut queue = CommitQueue::new();
+        for slot in 10..14 {
+            queue.dispatch(slot);
+        }
+
+        // 12 and 11 finish first: nothing can commit until 10 is done
+        queue.complete(12, block());
+        queue.complete(11, None);
+        assert!(queue.pop_ready().is_none());
+
+        queue.complete(10, block());
+        let committed: Vec<u64> = std::iter::from_fn(|| queue.pop_ready())
+            .map(|(slot, _)| slot)
+            .collect();
+        assert_eq!(committed, vec![10, 11, 12]);
+
+        // 13 still in flight
+        assert_eq!(queue.pending(), 1);
+        queue.complete(13, block());
+        assert_eq!(queue.pop_ready().map(|(slot, _)| slot), Some(13));
+        assert_eq!(queue.pending(), 0);
+    }
+
+    /// Deterministic source: starts at slot 100, tip then moves to 102;
+    /// slot 101 is skipped and slot 100 is slow
+    #[derive(Default)]
+    struct FakeSource {
+        slot_calls: std::sync::atomic::AtomicU64,
+
+        /// getSlot call (counting from 0) that fails, if any
+        failing_slot_call: Option<u64>,
+    }
+
+    impl SlotSource for FakeSource {
+        async fn get_latest_slot(&self) -> Result<u64, RpcError> {
+            let calls = self
+                .slot_calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            if self.failing_slot_call == Some(calls) {
+                return Err(RpcError::Http {
+                    status: 503,
+                    retry_after: None,
+                });
+            }
+            Ok(if calls == 0 { 100 } else { 102 })
+        }
+    }
+
+    impl BlockSource for FakeSource {
+        async fn get_block(&self, slot: u64) -> Result<Option<BlockData>, RpcError> {
+            if slot == 100 {
+                // Finishes after 102, which must still be committed last
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            if slot == 101 {
+                return Ok(None);
+            }
+
+            let block = json!({
+                "transactions": [{
+                    "meta": {
+                        "err": null,
+                        "logMessages": [
+                            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+                            format!("Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed {} of 200000 compute units", slot * 100),
+                            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success"
+                        ]
+                    },
+                    "transaction": {
+                        "message": {
+                            "accountKeys": ["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"],
+                            "instructions": [{"programIdIndex": 0}]
+                        }
+                    }
+                }]
+            });
+            Ok(Some(serde_json::from_value(block).unwrap()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_monitor_with_fake_source() {
+        let config = MonitorConfig {
+            poll_interval: Duration::from_millis(10),
+            ..MonitorConfig::default()
+        };
+        let monitor = NetworkMonitor::with_source(config, FakeSource::default());
+        let state = monitor.get_state();
+
+        let pipeline = tokio::spawn(async move { monitor.start().await });
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        loop {
+            if state.read().await.current_slot == 102 {
+                break;
+            }
+            assert!(tokio::time::Instant::now() < deadline, "pipeline stalled");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        pipeline.abort();
+
+        let state = state.read().await;
+        let jupiter = &state.programs["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"];
+        assert_eq!(jupiter.total_transactions(), 2); // slots 100 and 102
+        assert_eq!(jupiter.min_cu(), 10_000);
+        assert_eq!(jupiter.max_cu(), 10_200);
+    }
+
+    #[tokio::test]
+    async fn test_slots_keep_flowing_after_tip_error() {
+        let config = MonitorConfig {
+            poll_interval: Duration::from_millis(10),
+            ..MonitorConfig::default()
+        };
+        let source = FakeSource {
+            failing_slot_call: Some(1),
+            ..FakeSource::default()
+        };
+        let monitor = NetworkMonitor::with_source(config, source);
+        let state = monitor.get_state();
+
+        let pipeline = tokio::spawn(async move { monitor.start().await });
+
+        // The second getSlot fails; the producer backs off and asks again
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        loop {
+            if state.read().await.current_slot == 102 {
+                break;
+            }
+            assert!(!pipeline.is_finished(), "pipeline stopped on a tip error");
+            assert!(tokio::time::Instant::now() < deadline, "pipeline stalled");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        pipeline.abort();
+
+        let state = state.read().await;
+        let jupiter = &state.programs["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"];
+        assert_eq!(jupiter.total_transactions(), 2); // slots 100 and 102
+    }
+
+    /// Holds every fetch until released; slot 101 panics
+    struct GatedSource {
+        gate: tokio::sync::Semaphore,
+    }
+
+    impl SlotSource for GatedSource {
+        async fn get_latest_slot(&self) -> Result<u64, RpcError> {
+            Ok(102)
+        }
+    }
+
+    impl BlockSource for GatedSource {
+        async fn get_block(&self, slot: u64) -> Result<Option<BlockData>, RpcError> {
+            self.gate.acquire().await.unwrap().forget();
+            if slot == 101 {
+                panic!("fetch task for slot 101 panicked");
+            }
+            FakeSource::default().get_block(slot).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_status_and_panicked_fetch() {
+        let state = Arc::new(RwLock::new(NetworkState::new(
+            Duration::from_secs(300),
+            750,
+        )));
+        let source = Arc::new(GatedSource {
+            gate: tokio::sync::Semaphore::new(0),
+        });
+        let (tx, rx) = mpsc::channel(10);
+
+        let consumer = tokio::spawn(NetworkMonitor::consume_slots(
+            Arc::clone(&state),
+            Arc::clone(&source),
+            rx,
+            4,
+            false,
+        ));
+        for slot in 100..=102 {
+            tx.send(slot).await.unwrap();
+        }
+        drop(tx);
+
+        // Nothing has completed yet, but the header already sees the fetches
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while state.read().await.blocks_in_flight < 3 {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "in-flight count not updated"
+            );
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        // Slot 101's panic is skipped; 102 still commits after it
+        source.gate.add_permits(3);
+        consumer.await.unwrap().unwrap();
+
+        let state = state.read().await;
+        assert_eq!(state.current_slot, 102);
+        assert_eq!(state.blocks_in_flight, 0);
+        let jupiter = &state.programs["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"];
+        assert_eq!(jupiter.total_transactions(), 2);
+    }
+}
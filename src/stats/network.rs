@@ -1,13 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 
-use crate::stats::program::SlotStats;
+use crate::stats::program::{ChainTime, SlotStats};
 
-use crate::rpc::{extract_program_cu, extract_program_cu_timed, BlockData};
+use crate::rpc::{
+    anchor_error_name, reconcile_cu, summarize_logs, AnchorErrorLog, BlockData, EndpointStatus,
+    InstructionError, ProgramCu, TransactionError,
+};
 
 use crate::stats::is_system_program;
 
-use super::ProgramStats;
+use super::{CuSketch, ProgramStats};
 use std::cmp::Reverse;
 
 /// Network-wide state containing all program statistics
@@ -18,18 +21,54 @@ pub struct NetworkState {
     /// Current slot being processed
     pub current_slot: u64,
 
+    /// Block time of the current slot, if known
+    current_block_time: Option<i64>,
+
     /// Latest network slot (for lag calculation)
     pub latest_network_slot: u64,
 
+    /// Health of each RPC endpoint (for display)
+    pub endpoints: Vec<EndpointStatus>,
+
+    /// WebSocket slot feed: None if polling, Some(connected) if enabled
+    pub websocket_connected: Option<bool>,
+
+    /// getBlock requests currently in flight
+    pub blocks_in_flight: usize,
+
+    /// Maximum concurrent getBlock requests
+    pub fetch_concurrency: usize,
+
     /// When we started monitoring
     start_time: Instant,
 
-    /// Target window duration (e.g., 5 minutes)
+    /// Target window duration (e.g., 5 minutes); older slots are evicted
     window_duration: Duration,
 
-    /// Ring buffer capacity (e.g., 750 slots for 5 min)
+    /// Upper bound on slots kept per program (e.g., 750 slots for 5 min)
     buffer_capacity: usize,
 
+    /// Transactions whose logs were cut short ("Log truncated")
+    pub truncated_log_txs: u64,
+
+    /// Transactions with no logs at all
+    pub missing_log_txs: u64,
+
+    /// Legacy-format transactions seen
+    pub legacy_txs: u64,
+
+    /// Versioned (v0) transactions seen
+    pub versioned_txs: u64,
+
+    /// Count programs invoked by inner (CPI) instructions, not just top-level ones
+    count_inner_instructions: bool,
+
+    /// Most programs tracked at once; the least active are dropped beyond this
+    max_programs: usize,
+
+    /// Programs first recorded in the current slot, dropped by the cap last
+    newcomers: HashSet<String>,
+
     /// Performance stats
     pub perf_stats: PerfStats,
 }
@@ -40,33 +79,155 @@ impl NetworkState {
         Self {
             programs: HashMap::new(),
             current_slot: 0,
+            current_block_time: None,
             latest_network_slot: 0,
+            endpoints: Vec::new(),
+            websocket_connected: None,
+            blocks_in_flight: 0,
+            fetch_concurrency: 1,
             start_time: Instant::now(),
             window_duration,
             buffer_capacity,
+            truncated_log_txs: 0,
+            missing_log_txs: 0,
+            legacy_txs: 0,
+            versioned_txs: 0,
+            count_inner_instructions: false,
+            max_programs: usize::MAX,
+            newcomers: HashSet::new(),
             perf_stats: PerfStats::new(),
         }
     }
 
     /// Record a transaction for a specific program
-    /// Note: This accumulates data for the current slot
+    /// Note: This accumulates data for the current slot; call `finish_slot`
+    /// once the slot's transactions are all in
     pub fn record_transaction(&mut self, program_id: String, cu_used: u64, success: bool) {
+        let mut sketch = CuSketch::new();
+        sketch.insert(cu_used);
+
+        let timestamp = Instant::now();
         let slot_stats = SlotStats {
-            timestamp: Instant::now(),
+            timestamp,
+            slot: self.current_slot,
+            block_time: None,
             total_cu: cu_used,
             tx_count: 1,
             success_count: if success { 1 } else { 0 },
+            invocation_count: 1,
+            program_failure_count: if success { 0 } else { 1 },
+            errors: Vec::new(),
             avg_cu: cu_used as f64,
             min_cu: cu_used,
             max_cu: cu_used,
+            cu_sketch: sketch.clone(),
+            exclusive_cu: cu_used,
+            exclusive_min_cu: cu_used,
+            exclusive_max_cu: cu_used,
+            exclusive_cu_sketch: sketch,
         };
 
+        self.record_slot(program_id, slot_stats);
+    }
+
+    /// Record a slot for a program, tracking it from now on if it is new
+    fn record_slot(&mut self, program_id: String, slot_stats: SlotStats) {
+        if !self.programs.contains_key(&program_id) {
+            self.newcomers.insert(program_id.clone());
+        }
         self.programs
             .entry(program_id.clone())
-            .or_insert_with(|| ProgramStats::new(program_id, self.buffer_capacity))
+            .or_insert_with(|| {
+                ProgramStats::new(program_id, self.buffer_capacity, self.window_duration)
+            })
             .record_slot(slot_stats);
     }
 
+    /// Expire old slots and enforce the program cap, once the current slot
+    /// is fully recorded
+    ///
+    /// Runs once per block rather than per transaction; programs the block
+    /// brought in are spared by the cap until the next one.
+    pub fn finish_slot(&mut self) {
+        self.expire_programs(self.chain_time());
+        self.enforce_program_cap();
+        self.newcomers.clear();
+    }
+
+    /// Where the chain is, as of the current slot
+    pub fn chain_time(&self) -> ChainTime {
+        ChainTime {
+            slot: self.current_slot,
+            block_time: self.current_block_time,
+        }
+    }
+
+    /// Evict slots older than the window from every program, and drop
+    /// programs that have nothing left in it
+    fn expire_programs(&mut self, now: ChainTime) {
+        let before = self.programs.len();
+        self.programs.retain(|_, stats| {
+            stats.expire(now);
+            !stats.is_empty()
+        });
+        self.perf_stats.idle_programs_evicted += (before - self.programs.len()) as u64;
+    }
+
+    /// Drop the least active programs until at most `max_programs` are left
+    ///
+    /// Least active = fewest transactions in the window, then least recently
+    /// seen. Programs new in the current slot only go if nothing else is left.
+    fn enforce_program_cap(&mut self) {
+        let excess = self.programs.len().saturating_sub(self.max_programs);
+        if excess == 0 {
+            return;
+        }
+
+        let mut ranked: Vec<_> = self
+            .programs
+            .values()
+            .map(|s| {
+                (
+                    self.newcomers.contains(&s.program_id),
+                    s.total_transactions(),
+                    s.last_seen(),
+                    s.program_id.clone(),
+                )
+            })
+            .collect();
+        ranked.select_nth_unstable(excess - 1);
+
+        for (_, _, _, program_id) in ranked.into_iter().take(excess) {
+            self.programs.remove(&program_id);
+        }
+        self.perf_stats.capped_programs_evicted += excess as u64;
+    }
+
+    /// Approximate bytes of memory held by per-program statistics
+    pub fn memory_usage(&self) -> usize {
+        self.programs
+            .iter()
+            .map(|(program_id, stats)| program_id.capacity() + stats.memory_usage())
+            .sum::<usize>()
+            + self.programs.capacity() * std::mem::size_of::<(String, ProgramStats)>()
+    }
+
+    /// Print the verbose performance and memory summary
+    pub fn print_perf_summary(&self) {
+        self.perf_stats
+            .print_summary(self.perf_stats.blocks_processed as usize);
+        println!("\n🧠 Memory:");
+        println!("  Programs tracked: {}", self.program_count());
+        println!(
+            "  Program stats: {:.2} MiB",
+            self.memory_usage() as f64 / (1024.0 * 1024.0)
+        );
+        println!(
+            "  Programs evicted: {} idle, {} over --max-programs",
+            self.perf_stats.idle_programs_evicted, self.perf_stats.capped_programs_evicted
+        );
+    }
+
     /// Update the current slot
     pub fn update_slot(&mut self, slot: u64) {
         self.current_slot = slot;
@@ -77,6 +238,33 @@ impl NetworkState {
         self.latest_network_slot = slot;
     }
 
+    /// Update the RPC endpoint health snapshot
+    pub fn update_endpoint_status(&mut self, endpoints: Vec<EndpointStatus>) {
+        self.endpoints = endpoints;
+    }
+
+    /// Update the WebSocket slot feed status
+    pub fn update_websocket_status(&mut self, connected: Option<bool>) {
+        self.websocket_connected = connected;
+    }
+
+    /// Also count invocations from inner (CPI) instructions
+    pub fn set_count_inner_instructions(&mut self, enabled: bool) {
+        self.count_inner_instructions = enabled;
+    }
+
+    /// Cap the number of programs tracked at once
+    pub fn set_max_programs(&mut self, max_programs: usize) {
+        self.max_programs = max_programs.max(1);
+        self.enforce_program_cap();
+    }
+
+    /// Update block fetcher concurrency (in flight / maximum)
+    pub fn update_fetch_status(&mut self, in_flight: usize, concurrency: usize) {
+        self.blocks_in_flight = in_flight;
+        self.fetch_concurrency = concurrency;
+    }
+
     /// Get statistics for all programs, sorted by transaction count
     pub fn get_program_stats(&self, hide_system: bool) -> Vec<&ProgramStats> {
         let mut stats: Vec<_> = self
@@ -95,6 +283,16 @@ impl NetworkState {
         stats
     }
 
+    /// Share of versioned (v0) transactions, 0.0 to 100.0
+    pub fn versioned_share(&self) -> f64 {
+        let total = self.legacy_txs + self.versioned_txs;
+        if total == 0 {
+            0.0
+        } else {
+            self.versioned_txs as f64 / total as f64 * 100.0
+        }
+    }
+
     /// Get the actual window duration (min of elapsed time and target window)
     pub fn actual_window(&self) -> Duration {
         let elapsed = self.start_time.elapsed();
@@ -117,19 +315,27 @@ impl NetworkState {
 
         // Update current slot
         self.update_slot(slot);
+        self.current_block_time = block_data.block_time;
 
         // Accumulate per-program statistics for this slot
-        // HashMap: program_id -> (total_cu, tx_count, success_count, all_cu_values)
+        // HashMap: program_id -> (total_cu, tx_count, success_count, CU sketches)
         let mut slot_data: HashMap<String, SlotAccumulator> = HashMap::new();
 
         // Process each transaction and accumulate
         for tx_data in &block_data.transactions {
-            if let Some((programs, success)) = self.extract_tx_data(tx_data, verbose) {
-                for (program_id, cu_used) in programs {
+            if let Some(tx) = self.extract_tx_data(tx_data, verbose) {
+                for (program_id, cu) in tx.programs {
+                    // The error counts against the program that raised it
+                    let error = if tx.failed_program.as_ref() == Some(&program_id) {
+                        tx.error.as_deref()
+                    } else {
+                        None
+                    };
+                    let invocations = tx.invocations.get(&program_id).copied().unwrap_or(0);
                     let acc = slot_data
                         .entry(program_id)
                         .or_insert_with(SlotAccumulator::new);
-                    acc.add_transaction(cu_used, success);
+                    acc.add_transaction(cu, invocations, tx.success, error);
                 }
             }
         }
@@ -137,60 +343,162 @@ impl NetworkState {
         // Now convert accumulated data to SlotStats and record
         let timestamp = Instant::now();
         for (program_id, acc) in slot_data {
-            let slot_stats = acc.into_slot_stats(timestamp);
+            let slot_stats = acc.into_slot_stats(timestamp, slot, block_data.block_time);
 
             // Get or create ProgramStats and record this slot
-            self.programs
-                .entry(program_id.clone())
-                .or_insert_with(|| ProgramStats::new(program_id, self.buffer_capacity))
-                .record_slot(slot_stats);
+            self.record_slot(program_id, slot_stats);
         }
+        self.finish_slot();
 
         if let Some(start_time) = start {
             self.perf_stats.process_block_time += start_time.elapsed();
+            self.perf_stats.blocks_processed += 1;
         }
     }
 
     /// Extract relevant data from a transaction
+    ///
+    /// Per-program CU comes from the logs, reconciled against the transaction's
+    /// `computeUnitsConsumed` so truncated or missing logs still add up.
     fn extract_tx_data(
         &mut self,
         tx_data: &crate::rpc::TransactionData,
         verbose: bool,
-    ) -> Option<(HashMap<String, u64>, bool)> {
+    ) -> Option<TxSummary> {
+        let meta = tx_data.meta.as_ref();
+
+        if tx_data.is_versioned() {
+            self.versioned_txs += 1;
+        } else {
+            self.legacy_txs += 1;
+        }
+
         // Check success
-        let success = tx_data
-            .meta
-            .as_ref()
-            .map(|meta| meta.err.is_none())
-            .unwrap_or(false);
-
-        // Extract ALL programs at once (returns HashMap<String, u64>)
-        let programs: HashMap<String, u64> = match tx_data
-            .meta
-            .as_ref()
-            .and_then(|meta| meta.log_messages.as_ref())
-        {
-            Some(logs) => {
-                if verbose {
-                    let (result, elapsed) = extract_program_cu_timed(logs);
-
-                    self.perf_stats.extract_cu_time += elapsed;
-                    self.perf_stats.extract_cu_calls += 1;
-
-                    result
-                } else {
-                    extract_program_cu(logs)
-                }
+        let success = meta.map(|meta| meta.err.is_none()).unwrap_or(false);
+
+        let start = if verbose { Some(Instant::now()) } else { None };
+
+        let summary = match meta.and_then(|meta| meta.log_messages.as_ref()) {
+            Some(logs) => summarize_logs(logs),
+            None => {
+                self.missing_log_txs += 1;
+                Default::default()
             }
-            None => HashMap::new(),
         };
+        if summary.truncated {
+            self.truncated_log_txs += 1;
+        }
+
+        let instruction_programs = tx_data.transaction.message.instruction_programs();
+        let mut programs = reconcile_cu(
+            &summary,
+            meta.and_then(|meta| meta.compute_units_consumed),
+            &instruction_programs,
+        );
+
+        // Blame the program that logged the failure; without logs, fall back
+        // to the top-level instruction named in the error
+        let failed_program = if success {
+            None
+        } else {
+            summary.failed_program.clone().or_else(|| {
+                meta.and_then(|meta| meta.err.as_ref())
+                    .and_then(TransactionError::instruction_index)
+                    .and_then(|index| instruction_programs.get(index).cloned())
+            })
+        };
+        let error = meta.and_then(|meta| meta.err.as_ref()).map(|err| {
+            error_label(
+                err,
+                summary.anchor_error.as_ref(),
+                failed_program.as_deref(),
+            )
+        });
+        if let Some(program_id) = &failed_program {
+            programs.entry(program_id.clone()).or_default();
+        }
+
+        // Builtins and precompiles never log "consumed"; count every invoked
+        // program from the instructions themselves, even with zero CU
+        let mut invocations: HashMap<String, u32> = HashMap::new();
+        let inner_programs = if self.count_inner_instructions {
+            tx_data.inner_instruction_programs()
+        } else {
+            Vec::new()
+        };
+        for program_id in instruction_programs.into_iter().chain(inner_programs) {
+            programs.entry(program_id.clone()).or_default();
+            *invocations.entry(program_id).or_insert(0) += 1;
+        }
+
+        if let Some(start_time) = start {
+            self.perf_stats.extract_cu_time += start_time.elapsed();
+            self.perf_stats.extract_cu_calls += 1;
+        }
 
         // If no programs found, skip this transaction
         if programs.is_empty() {
             return None;
         }
 
-        Some((programs, success))
+        Some(TxSummary {
+            programs,
+            success,
+            failed_program,
+            error,
+            invocations,
+        })
+    }
+}
+
+/// Per-transaction result of `extract_tx_data`
+struct TxSummary {
+    /// Program ID -> CU attributed to it
+    programs: HashMap<String, ProgramCu>,
+
+    /// The transaction succeeded
+    success: bool,
+
+    /// Program that raised the error, if the transaction failed and we can tell
+    failed_program: Option<String>,
+
+    /// Histogram label for the transaction's error
+    error: Option<String>,
+
+    /// Program ID -> instructions that invoked it
+    invocations: HashMap<String, u32>,
+}
+
+/// Short label for an error, used as its histogram bucket
+///
+/// Custom codes are only named when `failed_program` logged an `AnchorError`:
+/// from that log line when its code matches, otherwise from Anchor's built-in
+/// error codes. Anything else keeps the raw code, since other programs reuse
+/// the same numbers, e.g. "SlippageToleranceExceeded (6001)" or
+/// "Custom 0x7d0 (2000)".
+fn error_label(
+    err: &TransactionError,
+    anchor: Option<&AnchorErrorLog>,
+    failed_program: Option<&str>,
+) -> String {
+    match err {
+        TransactionError::InstructionError {
+            error: InstructionError::Custom(code),
+            ..
+        } => {
+            let anchor = anchor.filter(|anchor| failed_program == Some(anchor.program_id.as_str()));
+            let name = match anchor {
+                Some(anchor) if anchor.code == *code => Some(anchor.name.as_str()),
+                Some(_) => anchor_error_name(*code),
+                None => None,
+            };
+            match name {
+                Some(name) => format!("{} ({})", name, code),
+                None => format!("Custom {:#x} ({})", code, code),
+            }
+        }
+        TransactionError::InstructionError { error, .. } => error.to_string(),
+        TransactionError::Other { .. } => err.to_string(),
     }
 }
 
@@ -199,7 +507,12 @@ struct SlotAccumulator {
     total_cu: u64,
     tx_count: u32,
     success_count: u32,
-    cu_values: Vec<u64>, // To calculate min/max/avg
+    invocation_count: u32,
+    program_failure_count: u32,
+    errors: HashMap<String, u32>, // error label -> count, for failures this program raised
+    cu_sketch: CuSketch,          // For min/max and percentiles
+    exclusive_cu: u64,
+    exclusive_cu_sketch: CuSketch,
 }
 
 impl SlotAccumulator {
@@ -208,23 +521,39 @@ impl SlotAccumulator {
             total_cu: 0,
             tx_count: 0,
             success_count: 0,
-            cu_values: Vec::new(),
+            invocation_count: 0,
+            program_failure_count: 0,
+            errors: HashMap::new(),
+            cu_sketch: CuSketch::new(),
+            exclusive_cu: 0,
+            exclusive_cu_sketch: CuSketch::new(),
         }
     }
 
-    fn add_transaction(&mut self, cu_used: u64, success: bool) {
-        self.total_cu += cu_used;
+    fn add_transaction(
+        &mut self,
+        cu: ProgramCu,
+        invocations: u32,
+        success: bool,
+        error: Option<&str>,
+    ) {
+        self.total_cu += cu.inclusive;
+        self.exclusive_cu += cu.exclusive;
+        self.cu_sketch.insert(cu.inclusive);
+        self.exclusive_cu_sketch.insert(cu.exclusive);
         self.tx_count += 1;
-        self.cu_values.push(cu_used); // TO DO: Here we are storing all cu values for this program,
-                                      // just to calculate min and max. This can be optimzied. But
-                                      // can we do more with this values maybe? p99?
+        self.invocation_count += invocations;
 
         if success {
             self.success_count += 1;
         }
+        if let Some(error) = error {
+            self.program_failure_count += 1;
+            *self.errors.entry(error.to_string()).or_insert(0) += 1;
+        }
     }
 
-    fn into_slot_stats(self, timestamp: Instant) -> SlotStats {
+    fn into_slot_stats(self, timestamp: Instant, slot: u64, block_time: Option<i64>) -> SlotStats {
         // Handle empty case for avg
         let avg_cu = if self.tx_count > 0 {
             self.total_cu as f64 / self.tx_count as f64
@@ -232,17 +561,24 @@ impl SlotAccumulator {
             0.0
         };
 
-        let min_cu = self.cu_values.iter().copied().min().unwrap_or(0);
-        let max_cu = self.cu_values.iter().copied().max().unwrap_or(0);
-
         SlotStats {
             timestamp,
+            slot,
+            block_time,
             total_cu: self.total_cu,
             tx_count: self.tx_count,
             success_count: self.success_count,
+            invocation_count: self.invocation_count,
+            program_failure_count: self.program_failure_count,
+            errors: self.errors.into_iter().collect(),
             avg_cu,
-            min_cu,
-            max_cu,
+            min_cu: self.cu_sketch.min(),
+            max_cu: self.cu_sketch.max(),
+            cu_sketch: self.cu_sketch,
+            exclusive_cu: self.exclusive_cu,
+            exclusive_min_cu: self.exclusive_cu_sketch.min(),
+            exclusive_max_cu: self.exclusive_cu_sketch.max(),
+            exclusive_cu_sketch: self.exclusive_cu_sketch,
         }
     }
 }
@@ -253,6 +589,9 @@ pub struct PerfStats {
     pub process_block_time: Duration,
     pub extract_cu_time: Duration,
     pub extract_cu_calls: u64,
+    pub blocks_processed: u64,
+    pub idle_programs_evicted: u64,
+    pub capped_programs_evicted: u64,
 }
 
 impl PerfStats {
@@ -285,8 +624,17 @@ impl PerfStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stats::CuMode;
     use std::time::Duration;
 
+    fn sketch_of(values: &[u64]) -> CuSketch {
+        let mut sketch = CuSketch::new();
+        for &value in values {
+            sketch.insert(value);
+        }
+        sketch
+    }
+
     #[test]
     fn test_network_state_with_slot_stats() {
         let mut state = NetworkState::new(
@@ -297,35 +645,59 @@ mod tests {
         // Manually create some SlotStats to test
         let slot1 = SlotStats {
             timestamp: Instant::now(),
+            slot: 1_000,
+            block_time: Some(1_700_000_000),
             total_cu: 100_000,
             tx_count: 2,
             success_count: 2,
+            invocation_count: 2,
+            program_failure_count: 0,
+            errors: Vec::new(),
             avg_cu: 50_000.0,
             min_cu: 42_000,
             max_cu: 58_000,
+            cu_sketch: sketch_of(&[42_000, 58_000]),
+            exclusive_cu: 100_000,
+            exclusive_min_cu: 42_000,
+            exclusive_max_cu: 58_000,
+            exclusive_cu_sketch: sketch_of(&[42_000, 58_000]),
         };
 
         let slot2 = SlotStats {
             timestamp: Instant::now(),
+            slot: 1_001,
+            block_time: Some(1_700_000_000),
             total_cu: 80_000,
             tx_count: 2,
             success_count: 1,
+            invocation_count: 3,
+            program_failure_count: 1,
+            errors: vec![("SlippageToleranceExceeded (6001)".to_string(), 1)],
             avg_cu: 40_000.0,
             min_cu: 38_000,
             max_cu: 42_000,
+            cu_sketch: sketch_of(&[38_000, 42_000]),
+            exclusive_cu: 80_000,
+            exclusive_min_cu: 38_000,
+            exclusive_max_cu: 42_000,
+            exclusive_cu_sketch: sketch_of(&[38_000, 42_000]),
         };
 
         // Record slots for Jupiter
         state
             .programs
             .entry("JUP4Fb2c".to_string())
-            .or_insert_with(|| ProgramStats::new("JUP4Fb2c".to_string(), 750))
+            .or_insert_with(|| {
+                ProgramStats::new("JUP4Fb2c".to_string(), 750, Duration::from_secs(300))
+            })
             .record_slot(slot1);
 
         state
             .programs
             .entry("JUP4Fb2c".to_string())
-            .or_insert_with(|| ProgramStats::new("JUP4Fb2c".to_string(), 750))
+            .or_insert_with(|| {
+                ProgramStats::new("JUP4Fb2c".to_string(), 750, Duration::from_secs(300))
+            })
             .record_slot(slot2);
 
         // Check program count
@@ -339,8 +711,127 @@ mod tests {
         let jupiter = stats[0];
         assert_eq!(jupiter.program_id, "JUP4Fb2c");
         assert_eq!(jupiter.total_transactions(), 4); // 2 + 2
+        assert_eq!(jupiter.total_invocations(), 5); // 2 + 3
         assert_eq!(jupiter.success_rate(), 75.0); // 3/4 * 100
+        assert_eq!(jupiter.tx_failure_rate(), 25.0);
+        assert_eq!(jupiter.program_failure_rate(), 25.0);
+        assert_eq!(
+            jupiter.error_histogram(),
+            vec![("SlippageToleranceExceeded (6001)".to_string(), 1)]
+        );
         assert_eq!(jupiter.min_cu(), 38_000);
         assert_eq!(jupiter.max_cu(), 58_000);
+        assert_eq!(jupiter.cu_percentile_for(CuMode::Inclusive, 0.0), 38_000);
+        assert_eq!(jupiter.cu_percentile_for(CuMode::Inclusive, 1.0), 58_000);
+    }
+
+    #[test]
+    fn test_error_label_names_only_anchor_programs() {
+        let custom = |code| TransactionError::InstructionError {
+            index: 0,
+            error: InstructionError::Custom(code),
+        };
+        let anchor_log = |program_id: &str, name: &str, code| AnchorErrorLog {
+            program_id: program_id.to_string(),
+            name: name.to_string(),
+            code,
+        };
+
+        // A non-Anchor program's code is not an Anchor error, even in Anchor's range
+        assert_eq!(
+            error_label(&custom(2000), None, Some("Tokenkeg")),
+            "Custom 0x7d0 (2000)"
+        );
+
+        // Neither is an AnchorError logged by another program in the CPI stack
+        let inner = anchor_log("Whirlpool", "ConstraintMut", 2000);
+        assert_eq!(
+            error_label(&custom(2000), Some(&inner), Some("Tokenkeg")),
+            "Custom 0x7d0 (2000)"
+        );
+
+        // The failing program's own log names it, then Anchor's built-in table
+        let own = anchor_log("JUP4Fb2c", "SlippageToleranceExceeded", 6001);
+        assert_eq!(
+            error_label(&custom(6001), Some(&own), Some("JUP4Fb2c")),
+            "SlippageToleranceExceeded (6001)"
+        );
+        assert_eq!(
+            error_label(&custom(2000), Some(&own), Some("JUP4Fb2c")),
+            "ConstraintMut (2000)"
+        );
+    }
+
+    #[test]
+    fn test_idle_programs_age_out_of_window() {
+        let mut state = NetworkState::new(Duration::from_secs(300), 750);
+        state.record_transaction("Quiet111".to_string(), 10_000, true);
+        let before = state.memory_usage();
+
+        // The chain moves on with no more activity from this program: 753
+        // slots is just over 5 minutes (its day-long rollups keep it around
+        // until they expire too)
+        let at_slot = |slot| ChainTime {
+            slot,
+            block_time: None,
+        };
+        state.expire_programs(at_slot(753));
+        assert!(state.programs.contains_key("Quiet111"));
+        state.expire_programs(at_slot(216_003));
+        assert!(!state.programs.contains_key("Quiet111"));
+        assert_eq!(state.perf_stats.idle_programs_evicted, 1);
+        assert!(state.memory_usage() < before);
+    }
+
+    #[test]
+    fn test_program_cap_drops_least_active() {
+        let mut state = NetworkState::new(Duration::from_secs(300), 750);
+        for _ in 0..3 {
+            state.record_transaction("Busy1111".to_string(), 10_000, true);
+        }
+        state.record_transaction("Quiet111".to_string(), 10_000, true);
+        for _ in 0..2 {
+            state.record_transaction("Steady11".to_string(), 10_000, true);
+        }
+        state.finish_slot();
+        let before = state.memory_usage();
+
+        state.set_max_programs(2);
+        assert_eq!(state.program_count(), 2);
+        assert!(!state.programs.contains_key("Quiet111"));
+        assert!(state.memory_usage() < before);
+        assert_eq!(state.perf_stats.capped_programs_evicted, 1);
+    }
+
+    #[test]
+    fn test_newcomer_survives_the_cap() {
+        let mut state = NetworkState::new(Duration::from_secs(300), 750);
+        state.set_max_programs(2);
+        for _ in 0..3 {
+            state.record_transaction("Busy1111".to_string(), 10_000, true);
+        }
+        for _ in 0..2 {
+            state.record_transaction("Steady11".to_string(), 10_000, true);
+        }
+        state.finish_slot();
+
+        // The block that brings a program in doesn't drop it again, even
+        // though it has the fewest transactions
+        state.update_slot(1);
+        state.record_transaction("New11111".to_string(), 10_000, true);
+        state.finish_slot();
+        assert_eq!(state.program_count(), 2);
+        assert!(state.programs.contains_key("New11111"));
+        assert!(state.programs.contains_key("Busy1111"));
+
+        // From the next block on it competes on activity like everyone else
+        state.update_slot(2);
+        for _ in 0..4 {
+            state.record_transaction("Late1111".to_string(), 10_000, true);
+        }
+        state.finish_slot();
+        assert!(state.programs.contains_key("Late1111"));
+        assert!(!state.programs.contains_key("New11111"));
+        assert_eq!(state.perf_stats.capped_programs_evicted, 2);
     }
 }
@@ -1,14 +1,80 @@
-use super::RingBuffer;
-use std::time::Instant;
+use super::rollup::Tier;
+use super::CuSketch;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+/// Per-minute rollups, kept for an hour
+const MINUTE_BUCKET: Duration = Duration::from_secs(60);
+const MINUTE_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Per-10-minute rollups, kept for a day
+const TEN_MINUTE_BUCKET: Duration = Duration::from_secs(10 * 60);
+const TEN_MINUTE_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
 
 /// Statistics for a single Solana program
 pub struct ProgramStats {
     /// The program's public key (e.g., "JUP4Fb2c...")
     pub program_id: String,
 
-    /// Ring buffer of slot-level statistics
+    /// Slot-level statistics for the window
     /// Each entry = aggregated stats for ONE SLOT
-    slot_timeline: RingBuffer<SlotStats>,
+    slots: Tier,
+
+    /// Per-minute rollups for the last hour
+    minutes: Tier,
+
+    /// Per-10-minute rollups for the last day
+    ten_minutes: Tier,
+}
+
+/// Which CU figure to report for a program
+///
+/// Logged CU is inclusive: a program's "consumed" line also covers every
+/// program it called via CPI, so summing across programs double counts.
+/// Exclusive (self) CU subtracts direct callees and sums to the real total.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CuMode {
+    #[default]
+    Inclusive,
+    Exclusive,
+}
+
+impl CuMode {
+    /// The other mode
+    pub fn toggle(self) -> Self {
+        match self {
+            CuMode::Inclusive => CuMode::Exclusive,
+            CuMode::Exclusive => CuMode::Inclusive,
+        }
+    }
+}
+
+/// Slot duration assumed when blocks carry no `blockTime`
+pub(super) const SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// A position on the chain: a slot and its block time (`blockTime`), if known
+///
+/// Windows and rollups are kept for a span of chain time, not of processing
+/// time, so a burst of backfilled blocks doesn't age out data that is still
+/// recent on chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainTime {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+impl ChainTime {
+    /// Chain time passed since `earlier`
+    ///
+    /// Uses blockTime when both have it, otherwise 400ms per slot.
+    pub fn since(&self, earlier: ChainTime) -> Duration {
+        match (self.block_time, earlier.block_time) {
+            (Some(now), Some(then)) => Duration::from_secs(now.saturating_sub(then).max(0) as u64),
+            _ => SLOT_DURATION.mul_f64(self.slot.saturating_sub(earlier.slot) as f64),
+        }
+    }
 }
 
 /// Statistics for a single slot
@@ -17,6 +83,12 @@ pub struct SlotStats {
     /// When this slot was processed
     pub timestamp: Instant,
 
+    /// Slot number
+    pub slot: u64,
+
+    /// When the block was produced, as Unix seconds (`blockTime`), if known
+    pub block_time: Option<i64>,
+
     /// Total compute units consumed in this slot
     pub total_cu: u64,
 
@@ -26,6 +98,15 @@ pub struct SlotStats {
     /// Number of successful transactions
     pub success_count: u32,
 
+    /// Number of instructions that invoked this program
+    pub invocation_count: u32,
+
+    /// Number of failed transactions where this program raised the error
+    pub program_failure_count: u32,
+
+    /// Error label -> count for those failures
+    pub errors: Vec<(String, u32)>,
+
     /// Average CU per transaction (precomputed)
     pub avg_cu: f64,
 
@@ -34,112 +115,1055 @@ pub struct SlotStats {
 
     /// Maximum CU in this slot
     pub max_cu: u64,
+
+    /// Distribution of per-transaction CU in this slot
+    pub cu_sketch: CuSketch,
+
+    /// Total exclusive (self) CU in this slot, excluding CPI callees
+    pub exclusive_cu: u64,
+
+    /// Minimum exclusive CU in this slot
+    pub exclusive_min_cu: u64,
+
+    /// Maximum exclusive CU in this slot
+    pub exclusive_max_cu: u64,
+
+    /// Distribution of per-transaction exclusive CU in this slot
+    pub exclusive_cu_sketch: CuSketch,
+}
+
+impl SlotStats {
+    /// Where this slot sits on the chain
+    pub fn chain_time(&self) -> ChainTime {
+        ChainTime {
+            slot: self.slot,
+            block_time: self.block_time,
+        }
+    }
+
+    /// Fold another slot (or bucket) into this one
+    ///
+    /// Keeps this one's `slot` and `block_time` (the start) and the latest
+    /// `timestamp`.
+    pub(super) fn merge(&mut self, other: &SlotStats) {
+        self.timestamp = self.timestamp.max(other.timestamp);
+        self.block_time = self.block_time.or(other.block_time);
+        self.total_cu += other.total_cu;
+        self.tx_count += other.tx_count;
+        self.success_count += other.success_count;
+        self.invocation_count += other.invocation_count;
+        self.program_failure_count += other.program_failure_count;
+        for (error, count) in &other.errors {
+            match self.errors.iter_mut().find(|(e, _)| e == error) {
+                Some((_, total)) => *total += count,
+                None => self.errors.push((error.clone(), *count)),
+            }
+        }
+        self.min_cu = self.min_cu.min(other.min_cu);
+        self.max_cu = self.max_cu.max(other.max_cu);
+        self.cu_sketch.merge(&other.cu_sketch);
+        self.exclusive_cu += other.exclusive_cu;
+        self.exclusive_min_cu = self.exclusive_min_cu.min(other.exclusive_min_cu);
+        self.exclusive_max_cu = self.exclusive_max_cu.max(other.exclusive_max_cu);
+        self.exclusive_cu_sketch.merge(&other.exclusive_cu_sketch);
+        self.avg_cu = if self.tx_count == 0 {
+            0.0
+        } else {
+            self.total_cu as f64 / self.tx_count as f64
+        };
+    }
+
+    /// Approximate heap bytes held beyond the struct itself
+    pub(super) fn heap_size(&self) -> usize {
+        self.errors.capacity() * size_of::<(String, u32)>()
+            + self.errors.iter().map(|(e, _)| e.capacity()).sum::<usize>()
+            + self.cu_sketch.heap_size()
+            + self.exclusive_cu_sketch.heap_size()
+    }
+}
+
+/// Sums and extremes over a run of slots
+///
+/// For the full window these are updated as slots enter and leave, so every
+/// read is O(1) however long the window is. This is what the UI hits for
+/// every program on every frame.
+#[derive(Clone)]
+pub(super) struct SlotTotals {
+    tx_count: u32,
+    success_count: u32,
+    invocation_count: u32,
+    program_failure_count: u32,
+    total_cu: u64,
+    exclusive_cu: u64,
+    errors: HashMap<String, u32>,
+    cu_sketch: CuSketch,
+    exclusive_cu_sketch: CuSketch,
+    min_cu: MonotonicDeque,
+    max_cu: MonotonicDeque,
+    exclusive_min_cu: MonotonicDeque,
+    exclusive_max_cu: MonotonicDeque,
+
+    /// Sequence number given to the next entry recorded
+    next_seq: u64,
+}
+
+impl SlotTotals {
+    pub(super) fn new() -> Self {
+        Self {
+            tx_count: 0,
+            success_count: 0,
+            invocation_count: 0,
+            program_failure_count: 0,
+            total_cu: 0,
+            exclusive_cu: 0,
+            errors: HashMap::new(),
+            cu_sketch: CuSketch::new(),
+            exclusive_cu_sketch: CuSketch::new(),
+            min_cu: MonotonicDeque::new(Extreme::Min),
+            max_cu: MonotonicDeque::new(Extreme::Max),
+            exclusive_min_cu: MonotonicDeque::new(Extreme::Min),
+            exclusive_max_cu: MonotonicDeque::new(Extreme::Max),
+            next_seq: 0,
+        }
+    }
+
+    /// Account for a slot entering the window
+    pub(super) fn add(&mut self, slot: &SlotStats) {
+        self.next_seq += 1;
+        self.extend(slot);
+    }
+
+    /// Account for a slot merged into the newest entry
+    pub(super) fn extend(&mut self, slot: &SlotStats) {
+        let seq = self.next_seq.saturating_sub(1);
+
+        self.tx_count += slot.tx_count;
+        self.success_count += slot.success_count;
+        self.invocation_count += slot.invocation_count;
+        self.program_failure_count += slot.program_failure_count;
+        self.total_cu += slot.total_cu;
+        self.exclusive_cu += slot.exclusive_cu;
+        for (error, count) in &slot.errors {
+            *self.errors.entry(error.clone()).or_insert(0) += count;
+        }
+        self.cu_sketch.merge(&slot.cu_sketch);
+        self.exclusive_cu_sketch.merge(&slot.exclusive_cu_sketch);
+        self.min_cu.push(seq, slot.min_cu);
+        self.max_cu.push(seq, slot.max_cu);
+        self.exclusive_min_cu.push(seq, slot.exclusive_min_cu);
+        self.exclusive_max_cu.push(seq, slot.exclusive_max_cu);
+    }
+
+    /// Account for entry `seq` leaving the window
+    pub(super) fn remove(&mut self, seq: u64, slot: &SlotStats) {
+        self.tx_count -= slot.tx_count;
+        self.success_count -= slot.success_count;
+        self.invocation_count -= slot.invocation_count;
+        self.program_failure_count -= slot.program_failure_count;
+        self.total_cu -= slot.total_cu;
+        self.exclusive_cu -= slot.exclusive_cu;
+        for (error, count) in &slot.errors {
+            if let Some(total) = self.errors.get_mut(error) {
+                *total -= count;
+                if *total == 0 {
+                    self.errors.remove(error);
+                }
+            }
+        }
+        self.min_cu.evict(seq);
+        self.max_cu.evict(seq);
+        self.exclusive_min_cu.evict(seq);
+        self.exclusive_max_cu.evict(seq);
+        self.cu_sketch
+            .remove(&slot.cu_sketch, self.min_cu.get(), self.max_cu.get());
+        self.exclusive_cu_sketch.remove(
+            &slot.exclusive_cu_sketch,
+            self.exclusive_min_cu.get(),
+            self.exclusive_max_cu.get(),
+        );
+    }
+
+    /// Sequence number the next entry will get
+    pub(super) fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Approximate heap bytes held (hash map overhead not counted)
+    pub(super) fn heap_size(&self) -> usize {
+        self.errors.capacity() * size_of::<(String, u32)>()
+            + self.errors.keys().map(String::capacity).sum::<usize>()
+            + self.cu_sketch.heap_size()
+            + self.exclusive_cu_sketch.heap_size()
+            + [
+                &self.min_cu,
+                &self.max_cu,
+                &self.exclusive_min_cu,
+                &self.exclusive_max_cu,
+            ]
+            .iter()
+            .map(|d| d.entries.capacity() * size_of::<(u64, u64)>())
+            .sum::<usize>()
+    }
+
+    fn total_cu(&self, mode: CuMode) -> u64 {
+        match mode {
+            CuMode::Inclusive => self.total_cu,
+            CuMode::Exclusive => self.exclusive_cu,
+        }
+    }
+
+    fn cu_sketch(&self, mode: CuMode) -> &CuSketch {
+        match mode {
+            CuMode::Inclusive => &self.cu_sketch,
+            CuMode::Exclusive => &self.exclusive_cu_sketch,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Extreme {
+    Min,
+    Max,
+}
+
+/// Sliding-window minimum or maximum
+///
+/// Keeps (sequence, value) pairs whose values only get worse from front to
+/// back: a value beaten by a newer one can never be the extreme again, so it
+/// is dropped on push. The front is always the current extreme.
+#[derive(Clone)]
+struct MonotonicDeque {
+    entries: VecDeque<(u64, u64)>,
+    extreme: Extreme,
+}
+
+impl MonotonicDeque {
+    fn new(extreme: Extreme) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            extreme,
+        }
+    }
+
+    fn push(&mut self, seq: u64, value: u64) {
+        while let Some(&(_, last)) = self.entries.back() {
+            let beaten = match self.extreme {
+                Extreme::Min => last >= value,
+                Extreme::Max => last <= value,
+            };
+            if !beaten {
+                break;
+            }
+            self.entries.pop_back();
+        }
+        self.entries.push_back((seq, value));
+    }
+
+    /// Drop entry `seq`, the oldest in the window
+    fn evict(&mut self, seq: u64) {
+        while self.entries.front().is_some_and(|&(front, _)| front <= seq) {
+            self.entries.pop_front();
+        }
+    }
+
+    fn get(&self) -> u64 {
+        self.entries.front().map_or(0, |&(_, value)| value)
+    }
 }
 
 impl ProgramStats {
     /// Create new program stats tracker
-    pub fn new(program_id: String, capacity: usize) -> Self {
+    ///
+    /// Keeps slots from the last `window`, and at most `capacity` of them,
+    /// plus per-minute rollups for an hour and per-10-minute rollups for a day.
+    pub fn new(program_id: String, capacity: usize, window: Duration) -> Self {
         Self {
             program_id,
-            slot_timeline: RingBuffer::new(capacity),
+            slots: Tier::per_slot(capacity, window),
+            minutes: Tier::rollup(MINUTE_BUCKET, MINUTE_RETENTION),
+            ten_minutes: Tier::rollup(TEN_MINUTE_BUCKET, TEN_MINUTE_RETENTION),
         }
     }
 
     /// Record statistics for a slot
     pub fn record_slot(&mut self, slot_stats: SlotStats) {
-        self.slot_timeline.push(slot_stats);
+        let now = slot_stats.chain_time();
+        self.minutes.record(slot_stats.clone());
+        self.ten_minutes.record(slot_stats.clone());
+        self.slots.record(slot_stats);
+        self.expire(now);
+    }
+
+    /// Evict slots and rollups older than their tier keeps, relative to `now`
+    ///
+    /// Called for every program as the chain moves on, so programs that
+    /// stopped showing up age out too instead of keeping stale slots forever.
+    pub fn expire(&mut self, now: ChainTime) {
+        for tier in self.tiers_mut() {
+            tier.expire(now);
+        }
+    }
+
+    /// Whether nothing is left in any tier, not even the day-long rollups
+    pub fn is_empty(&self) -> bool {
+        self.tiers().iter().all(|tier| tier.is_empty())
+    }
+
+    /// When this program was last seen, if it has data in any tier
+    pub fn last_seen(&self) -> Option<Instant> {
+        self.tiers()
+            .iter()
+            .filter_map(|tier| tier.last_seen())
+            .max()
+    }
+
+    /// Approximate bytes of memory held by this program's stats
+    pub fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self.program_id.capacity()
+            + self
+                .tiers()
+                .iter()
+                .map(|tier| tier.heap_size())
+                .sum::<usize>()
+    }
+
+    /// Per-slot stats in the window, oldest first
+    pub fn slot_history(&self) -> impl DoubleEndedIterator<Item = &SlotStats> {
+        self.slots.iter()
+    }
+
+    /// The last `horizon` split into `points` equal runs of slots ending at
+    /// `current_slot`, oldest first
+    ///
+    /// Read from the same tier as `over`. Runs are never shorter than that
+    /// tier's buckets and hold whole buckets, so long horizons may come back
+    /// with fewer points. Runs where this program did nothing are zero (with
+    /// no success rate).
+    pub fn history(
+        &self,
+        points: usize,
+        horizon: Duration,
+        mode: CuMode,
+        current_slot: u64,
+    ) -> Vec<HistoryPoint> {
+        let tier = self.tier_for(horizon);
+        let bucket_slots = tier.bucket_slots();
+        let horizon_slots = horizon.as_millis().div_ceil(SLOT_DURATION.as_millis()) as u64;
+        let step = horizon_slots
+            .div_ceil(points.max(1) as u64)
+            .next_multiple_of(bucket_slots)
+            .max(1);
+        let points = horizon_slots.div_ceil(step).max(1);
+        // Runs end with the bucket holding `current_slot`
+        let last_slot = current_slot / bucket_slots * bucket_slots + bucket_slots - 1;
+        let from_slot = last_slot.saturating_sub(step * points);
+
+        // (transactions, successes, CU) per run
+        let mut runs = vec![(0u64, 0u64, 0u64); points as usize];
+        for (start, slot) in tier
+            .iter_from()
+            .filter(|&(start, _)| start > from_slot && start <= current_slot)
+        {
+            let run = &mut runs[((start - from_slot - 1) / step) as usize];
+            run.0 += slot.tx_count as u64;
+            run.1 += slot.success_count as u64;
+            run.2 += match mode {
+                CuMode::Inclusive => slot.total_cu,
+                CuMode::Exclusive => slot.exclusive_cu,
+            };
+        }
+
+        let secs = step as f64 * SLOT_DURATION.as_secs_f64();
+        runs.into_iter()
+            .map(|(txs, successes, cu)| HistoryPoint {
+                transactions_per_second: txs as f64 / secs,
+                cu_per_second: cu as f64 / secs,
+                success_rate: (txs > 0).then(|| successes as f64 / txs as f64 * 100.0),
+            })
+            .collect()
+    }
+
+    /// Finest to coarsest
+    fn tiers(&self) -> [&Tier; 3] {
+        [&self.slots, &self.minutes, &self.ten_minutes]
+    }
+
+    fn tiers_mut(&mut self) -> [&mut Tier; 3] {
+        [&mut self.slots, &mut self.minutes, &mut self.ten_minutes]
+    }
+
+    /// Metrics over the whole window
+    pub fn window(&self) -> StatsView<'_> {
+        self.slots.whole()
+    }
+
+    /// Metrics over the last `horizon` of chain time, up to `now`
+    ///
+    /// Walks only the slots inside the horizon. Rates are per second of the
+    /// horizon, or of the chain time since this program's oldest slot if that
+    /// is shorter.
+    pub fn recent(&self, horizon: Duration, now: ChainTime) -> StatsView<'_> {
+        self.slots.view(horizon, now)
+    }
+
+    /// Metrics over any horizon up to a day, from the finest tier that covers it
+    ///
+    /// Per-slot data within the window, per-minute rollups up to an hour and
+    /// per-10-minute rollups beyond, so longer horizons are approximate at
+    /// their oldest edge.
+    pub fn over(&self, horizon: Duration, now: ChainTime) -> StatsView<'_> {
+        self.tier_for(horizon).view(horizon, now)
+    }
+
+    /// The finest tier reaching back `horizon`, or the coarsest
+    fn tier_for(&self, horizon: Duration) -> &Tier {
+        self.tiers()
+            .into_iter()
+            .find(|tier| tier.retention() >= horizon)
+            .unwrap_or(&self.ten_minutes)
+    }
+
+    /// How far back the slot window reaches
+    pub fn window_duration(&self) -> Duration {
+        self.slots.retention()
     }
 
     /// Get total transaction count across all slots in buffer
     pub fn total_transactions(&self) -> u32 {
-        self.slot_timeline.iter().map(|s| s.tx_count).sum()
+        self.slots.totals().tx_count
+    }
+
+    /// Get total instruction invocations across all slots in buffer
+    pub fn total_invocations(&self) -> u32 {
+        self.slots.totals().invocation_count
     }
 
     /// Calculate success rate (0.0 to 100.0)
     pub fn success_rate(&self) -> f64 {
-        let success_txs: u32 = self.slot_timeline.iter().map(|s| s.success_count).sum();
-        let all_txs = self.total_transactions();
+        self.window().success_rate()
+    }
 
-        if all_txs == 0 {
-            100.0
-        } else {
-            (success_txs as f64 / all_txs as f64) * 100.0
-        }
+    /// Percentage (0.0 to 100.0) of transactions this program was part of that failed
+    ///
+    /// Counts every failure, including ones raised by another program in the
+    /// same transaction.
+    pub fn tx_failure_rate(&self) -> f64 {
+        self.window().tx_failure_rate()
+    }
+
+    /// Percentage (0.0 to 100.0) of transactions where this program itself failed
+    pub fn program_failure_rate(&self) -> f64 {
+        self.window().program_failure_rate()
+    }
+
+    /// Errors this program raised across all slots, most frequent first
+    pub fn error_histogram(&self) -> Vec<(String, u32)> {
+        self.window().error_histogram()
     }
 
     /// Calculate transactions per second
     pub fn transactions_per_second(&self) -> f64 {
-        if self.slot_timeline.is_empty() {
-            0.0
-        } else {
-            let time_span = self.get_time_span();
-            let total_txs = self.total_transactions();
-
-            total_txs as f64 / time_span
-        }
+        self.window().transactions_per_second()
     }
 
     /// Calculate compute units per second
     pub fn cu_per_second(&self) -> f64 {
-        if self.slot_timeline.is_empty() {
-            0.0
-        } else {
-            let time_span = self.get_time_span();
-            let total_cu: u64 = self.slot_timeline.iter().map(|s| s.total_cu).sum();
+        self.cu_per_second_for(CuMode::Inclusive)
+    }
 
-            total_cu as f64 / time_span
-        }
+    /// Calculate compute units per second, inclusive or exclusive of CPI callees
+    pub fn cu_per_second_for(&self, mode: CuMode) -> f64 {
+        self.window().cu_per_second_for(mode)
     }
 
     /// Calculate average CU per transaction across all slots
     pub fn avg_cu_per_transaction(&self) -> f64 {
-        if self.slot_timeline.is_empty() {
-            0.0
-        } else {
-            let total_cu: u64 = self.slot_timeline.iter().map(|s| s.total_cu).sum();
-            let total_txs = self.total_transactions();
+        self.avg_cu_per_transaction_for(CuMode::Inclusive)
+    }
 
-            total_cu as f64 / total_txs as f64
-        }
+    /// Calculate average CU per transaction, inclusive or exclusive of CPI callees
+    pub fn avg_cu_per_transaction_for(&self, mode: CuMode) -> f64 {
+        self.window().avg_cu_per_transaction_for(mode)
     }
 
     /// Get minimum CU from all slots
     pub fn min_cu(&self) -> u64 {
-        self.slot_timeline
-            .iter()
-            .map(|s| s.min_cu)
-            .min()
-            .unwrap_or(0)
+        self.min_cu_for(CuMode::Inclusive)
+    }
+
+    /// Get minimum CU from all slots, inclusive or exclusive of CPI callees
+    pub fn min_cu_for(&self, mode: CuMode) -> u64 {
+        self.window().min_cu_for(mode)
     }
 
     /// Get maximum CU from all slots
     pub fn max_cu(&self) -> u64 {
-        self.slot_timeline
+        self.max_cu_for(CuMode::Inclusive)
+    }
+
+    /// Get maximum CU from all slots, inclusive or exclusive of CPI callees
+    pub fn max_cu_for(&self, mode: CuMode) -> u64 {
+        self.window().max_cu_for(mode)
+    }
+
+    /// Per-transaction CU distribution over the whole window
+    pub fn cu_sketch_for(&self, mode: CuMode) -> CuSketch {
+        self.window().cu_sketch_for(mode).clone()
+    }
+
+    /// Per-transaction CU at quantile `q` (0.0 to 1.0) over the window, within 1%
+    pub fn cu_percentile_for(&self, mode: CuMode, q: f64) -> u64 {
+        self.window().cu_percentile_for(mode, q)
+    }
+}
+
+/// Metrics over part of a program's history
+///
+/// Either the whole window (`ProgramStats::window`, borrowed from the running
+/// totals) or a recent horizon (`ProgramStats::recent`, summed on demand).
+pub struct StatsView<'a> {
+    totals: Cow<'a, SlotTotals>,
+
+    /// Chain time covered, in seconds (at least 1)
+    time_span: f64,
+}
+
+impl<'a> StatsView<'a> {
+    pub(super) fn new(totals: Cow<'a, SlotTotals>, time_span: f64) -> Self {
+        Self { totals, time_span }
+    }
+
+    /// Transactions in the span
+    pub fn total_transactions(&self) -> u32 {
+        self.totals.tx_count
+    }
+
+    /// Instruction invocations in the span
+    pub fn total_invocations(&self) -> u32 {
+        self.totals.invocation_count
+    }
+
+    /// Success rate (0.0 to 100.0)
+    pub fn success_rate(&self) -> f64 {
+        let all_txs = self.totals.tx_count;
+
+        if all_txs == 0 {
+            100.0
+        } else {
+            (self.totals.success_count as f64 / all_txs as f64) * 100.0
+        }
+    }
+
+    /// Percentage (0.0 to 100.0) of transactions that failed, whoever raised the error
+    pub fn tx_failure_rate(&self) -> f64 {
+        100.0 - self.success_rate()
+    }
+
+    /// Percentage (0.0 to 100.0) of transactions where this program itself failed
+    pub fn program_failure_rate(&self) -> f64 {
+        let all_txs = self.totals.tx_count;
+
+        if all_txs == 0 {
+            0.0
+        } else {
+            (self.totals.program_failure_count as f64 / all_txs as f64) * 100.0
+        }
+    }
+
+    /// Errors this program raised, most frequent first
+    pub fn error_histogram(&self) -> Vec<(String, u32)> {
+        let mut histogram: Vec<(String, u32)> = self
+            .totals
+            .errors
             .iter()
-            .map(|s| s.max_cu)
-            .max()
-            .unwrap_or(0)
+            .map(|(error, &count)| (error.clone(), count))
+            .collect();
+        histogram.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        histogram
+    }
+
+    /// Transactions per second
+    pub fn transactions_per_second(&self) -> f64 {
+        self.totals.tx_count as f64 / self.time_span
+    }
+
+    /// Compute units per second, inclusive or exclusive of CPI callees
+    pub fn cu_per_second_for(&self, mode: CuMode) -> f64 {
+        self.totals.total_cu(mode) as f64 / self.time_span
+    }
+
+    /// Average CU per transaction, inclusive or exclusive of CPI callees
+    pub fn avg_cu_per_transaction_for(&self, mode: CuMode) -> f64 {
+        if self.totals.tx_count == 0 {
+            0.0
+        } else {
+            self.totals.total_cu(mode) as f64 / self.totals.tx_count as f64
+        }
+    }
+
+    /// Minimum per-transaction CU, inclusive or exclusive of CPI callees
+    pub fn min_cu_for(&self, mode: CuMode) -> u64 {
+        match mode {
+            CuMode::Inclusive => self.totals.min_cu.get(),
+            CuMode::Exclusive => self.totals.exclusive_min_cu.get(),
+        }
+    }
+
+    /// Maximum per-transaction CU, inclusive or exclusive of CPI callees
+    pub fn max_cu_for(&self, mode: CuMode) -> u64 {
+        match mode {
+            CuMode::Inclusive => self.totals.max_cu.get(),
+            CuMode::Exclusive => self.totals.exclusive_max_cu.get(),
+        }
+    }
+
+    /// Per-transaction CU distribution
+    pub fn cu_sketch_for(&self, mode: CuMode) -> &CuSketch {
+        self.totals.cu_sketch(mode)
+    }
+
+    /// Per-transaction CU at quantile `q` (0.0 to 1.0), within 1%
+    pub fn cu_percentile_for(&self, mode: CuMode, q: f64) -> u64 {
+        self.totals.cu_sketch(mode).quantile(q)
+    }
+}
+
+/// A program's activity over one run of slots (see `ProgramStats::history`)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HistoryPoint {
+    pub transactions_per_second: f64,
+    pub cu_per_second: f64,
+
+    /// Success rate (0.0 to 100.0), None if there were no transactions
+    pub success_rate: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(300);
+
+    /// A chain position known only by its slot
+    fn at_slot(slot: u64) -> ChainTime {
+        ChainTime {
+            slot,
+            block_time: None,
+        }
+    }
+
+    fn slot(cu: &[u64], errors: &[&str]) -> SlotStats {
+        let mut sketch = CuSketch::new();
+        for &value in cu {
+            sketch.insert(value);
+        }
+        let total_cu: u64 = cu.iter().sum();
+        SlotStats {
+            timestamp: Instant::now(),
+            slot: 0,
+            block_time: None,
+            total_cu,
+            tx_count: cu.len() as u32,
+            success_count: (cu.len() - errors.len()) as u32,
+            invocation_count: cu.len() as u32,
+            program_failure_count: errors.len() as u32,
+            errors: errors.iter().map(|e| (e.to_string(), 1)).collect(),
+            avg_cu: total_cu as f64 / cu.len() as f64,
+            min_cu: sketch.min(),
+            max_cu: sketch.max(),
+            cu_sketch: sketch.clone(),
+            exclusive_cu: total_cu / 2,
+            exclusive_min_cu: sketch.min() / 2,
+            exclusive_max_cu: sketch.max() / 2,
+            exclusive_cu_sketch: sketch,
+        }
+    }
+
+    #[test]
+    fn test_totals_follow_eviction() {
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 3, WINDOW);
+        stats.record_slot(slot(&[90_000, 10_000], &["Slippage"]));
+        stats.record_slot(slot(&[50_000], &[]));
+        stats.record_slot(slot(&[20_000, 70_000], &["Slippage"]));
+        assert_eq!(stats.total_transactions(), 5);
+        assert_eq!(stats.min_cu(), 10_000);
+        assert_eq!(stats.max_cu(), 90_000);
+
+        // Pushes out the slot holding both the min and the max
+        stats.record_slot(slot(&[40_000], &[]));
+        assert_eq!(stats.total_transactions(), 4);
+        assert_eq!(stats.total_invocations(), 4);
+        assert_eq!(stats.success_rate(), 75.0);
+        assert_eq!(stats.min_cu(), 20_000);
+        assert_eq!(stats.max_cu(), 70_000);
+        assert_eq!(stats.min_cu_for(CuMode::Exclusive), 10_000);
+        assert_eq!(stats.avg_cu_per_transaction(), 45_000.0);
+        assert_eq!(stats.cu_percentile_for(CuMode::Inclusive, 0.0), 20_000);
+        assert_eq!(stats.cu_percentile_for(CuMode::Inclusive, 1.0), 70_000);
+        assert_eq!(stats.error_histogram(), vec![("Slippage".to_string(), 1)]);
+
+        stats.record_slot(slot(&[30_000], &[]));
+        stats.record_slot(slot(&[60_000], &[]));
+        assert_eq!(stats.min_cu(), 30_000);
+        assert_eq!(stats.max_cu(), 60_000);
+        assert_eq!(stats.program_failure_rate(), 0.0);
+        assert!(stats.error_histogram().is_empty());
+    }
+
+    #[test]
+    fn test_totals_match_full_scan() {
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 16, WINDOW);
+        for i in 0..200u64 {
+            stats.record_slot(slot(&[(i * 7919) % 100_000 + 1, i * 13 + 5], &[]));
+
+            let window: Vec<_> = stats.slot_history().collect();
+            let total_txs: u32 = window.iter().map(|s| s.tx_count).sum();
+            let min = window.iter().map(|s| s.min_cu).min().unwrap();
+            let max = window.iter().map(|s| s.max_cu).max().unwrap();
+            let mut sketch = CuSketch::new();
+            for s in &window {
+                sketch.merge(&s.cu_sketch);
+            }
+
+            assert_eq!(stats.total_transactions(), total_txs);
+            assert_eq!(stats.min_cu(), min);
+            assert_eq!(stats.max_cu(), max);
+            assert_eq!(stats.cu_sketch_for(CuMode::Inclusive), sketch);
+        }
+    }
+
+    #[test]
+    fn test_old_slots_expire() {
+        const START: i64 = 1_700_000_000;
+        let at = |secs: u64, cu: u64| SlotStats {
+            slot: 1_000 + secs * 5 / 2,
+            block_time: Some(START + secs as i64),
+            ..slot(&[cu], &[])
+        };
+        let chain_at = |secs: u64| at(secs, 0).chain_time();
+
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+        stats.record_slot(at(0, 90_000));
+        stats.record_slot(at(100, 10_000));
+        stats.record_slot(at(250, 50_000));
+        assert_eq!(stats.total_transactions(), 3);
+
+        // A new slot pushes the first one out of the window
+        stats.record_slot(at(350, 40_000));
+        assert_eq!(stats.total_transactions(), 3);
+        assert_eq!(stats.max_cu(), 50_000);
+
+        // The chain moving on without new slots ages out the rest
+        stats.expire(chain_at(560));
+        assert_eq!(stats.total_transactions(), 1);
+        assert_eq!(stats.min_cu(), 40_000);
+
+        stats.expire(chain_at(700));
+        assert_eq!(stats.total_transactions(), 0);
+        assert_eq!(stats.max_cu(), 0);
+        assert_eq!(stats.cu_percentile_for(CuMode::Inclusive, 0.5), 0);
+
+        // The rollups still remember it until they age out too
+        assert!(!stats.is_empty());
+        stats.expire(chain_at(25 * 60 * 60));
+        assert!(stats.is_empty());
+    }
+
+    #[test]
+    fn test_expiry_uses_chain_time() {
+        // Backfilled in one burst, but 4 minutes apart on chain
+        let processed = Instant::now();
+        let at = |number: u64, block_time: Option<i64>| SlotStats {
+            timestamp: processed,
+            slot: number,
+            block_time,
+            ..slot(&[10_000], &[])
+        };
+
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+        stats.record_slot(at(1_000, Some(1_700_000_000)));
+        stats.record_slot(at(1_600, Some(1_700_000_240)));
+        assert_eq!(stats.total_transactions(), 2);
+
+        // Another 2 minutes of chain time pushes the first one out, however
+        // little processing time has passed
+        stats.record_slot(at(1_900, Some(1_700_000_360)));
+        assert_eq!(stats.total_transactions(), 2);
+
+        // Without blockTime, 400ms per slot: 751 slots is past the window
+        stats.expire(ChainTime {
+            slot: 2_351,
+            block_time: None,
+        });
+        assert_eq!(stats.total_transactions(), 1);
+    }
+
+    #[test]
+    fn test_rates_use_chain_time() {
+        let at = |number: u64, block_time: Option<i64>| SlotStats {
+            slot: number,
+            block_time,
+            ..slot(&[10_000, 10_000], &[])
+        };
+
+        // Processed in the same instant (catching up), but 20s apart on chain
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+        stats.record_slot(at(1_000, Some(1_700_000_000)));
+        stats.record_slot(at(1_050, Some(1_700_000_020)));
+        assert_eq!(stats.transactions_per_second(), 4.0 / 20.0);
+        assert_eq!(stats.cu_per_second(), 40_000.0 / 20.0);
+
+        // Without blockTime, 400ms per slot
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+        stats.record_slot(at(1_000, None));
+        stats.record_slot(at(1_050, Some(1_700_000_020)));
+        assert_eq!(stats.transactions_per_second(), 4.0 / 20.0);
+    }
+
+    #[test]
+    fn test_recent_horizon() {
+        let at = |number: u64, cu: &[u64]| SlotStats {
+            slot: number,
+            ..slot(cu, &[])
+        };
+
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+        stats.record_slot(at(1_000, &[90_000, 90_000]));
+        stats.record_slot(at(1_100, &[10_000]));
+        stats.record_slot(at(1_120, &[20_000, 30_000, 40_000]));
+
+        // 10s = 25 slots back from 1_130: only the last slot
+        let live = stats.recent(Duration::from_secs(10), at_slot(1_130));
+        assert_eq!(live.total_transactions(), 3);
+        assert_eq!(live.transactions_per_second(), 3.0 / 10.0);
+        assert_eq!(live.min_cu_for(CuMode::Inclusive), 20_000);
+        assert_eq!(live.max_cu_for(CuMode::Inclusive), 40_000);
+
+        // 30s = 75 slots: everything but the first slot
+        let half_minute = stats.recent(Duration::from_secs(30), at_slot(1_130));
+        assert_eq!(half_minute.total_transactions(), 4);
+        assert_eq!(
+            half_minute.avg_cu_per_transaction_for(CuMode::Inclusive),
+            25_000.0
+        );
+
+        // The window still sees it all
+        assert_eq!(stats.window().total_transactions(), 6);
+        assert_eq!(stats.window().max_cu_for(CuMode::Inclusive), 90_000);
+
+        // Quiet lately: nothing in the horizon
+        let idle = stats.recent(Duration::from_secs(10), at_slot(1_500));
+        assert_eq!(idle.total_transactions(), 0);
+        assert_eq!(idle.transactions_per_second(), 0.0);
+        assert_eq!(idle.cu_percentile_for(CuMode::Inclusive, 0.5), 0);
+    }
+
+    #[test]
+    fn test_recent_rate_before_horizon_fills() {
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+        for number in 1_000..1_005 {
+            stats.record_slot(SlotStats {
+                slot: number,
+                ..slot(&[10_000], &[])
+            });
+        }
+
+        // Only 1.6s of chain time so far (slot 1_000 to 1_004): 5 txs over
+        // 1.6s, not over the 10s horizon
+        let live = stats.recent(Duration::from_secs(10), at_slot(1_004));
+        assert_eq!(live.transactions_per_second(), 5.0 / 1.6);
+    }
+
+    #[test]
+    fn test_recent_uses_block_time() {
+        let at = |number: u64, block_time: i64| SlotStats {
+            slot: number,
+            block_time: Some(block_time),
+            ..slot(&[10_000, 10_000], &[])
+        };
+
+        // 50 slots apart, but 40s on chain rather than 20s
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+        stats.record_slot(at(1_000, 1_700_000_000));
+        stats.record_slot(at(1_050, 1_700_000_040));
+        let now = stats.slot_history().last().unwrap().chain_time();
+
+        let minute = stats.recent(Duration::from_secs(60), now);
+        assert_eq!(minute.total_transactions(), 4);
+        assert_eq!(minute.transactions_per_second(), 4.0 / 40.0);
+
+        // Only the last slot is within 30s of chain time
+        let half_minute = stats.recent(Duration::from_secs(30), now);
+        assert_eq!(half_minute.total_transactions(), 2);
+        assert_eq!(half_minute.transactions_per_second(), 2.0 / 30.0);
+    }
+
+    #[test]
+    fn test_live_agrees_with_window_over_irregular_block_times() {
+        // Evenly spaced slots whose block times drift: 7s, 25s and 13s apart
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+        for (i, (block_time, cu)) in [(0, 90_000), (7, 10_000), (32, 50_000), (45, 40_000)]
+            .into_iter()
+            .enumerate()
+        {
+            stats.record_slot(SlotStats {
+                slot: 1_000 + i as u64 * 50,
+                block_time: Some(1_700_000_000 + block_time),
+                ..slot(&[cu, cu], &[])
+            });
+        }
+        let now = stats.slot_history().last().unwrap().chain_time();
+
+        // A Live horizon reaching back past the oldest slot sees the same
+        // 45s of chain time as the whole window
+        let window = stats.window();
+        let live = stats.recent(Duration::from_secs(60), now);
+        assert_eq!(live.total_transactions(), window.total_transactions());
+        assert_eq!(window.transactions_per_second(), 8.0 / 45.0);
+        assert_eq!(
+            live.transactions_per_second(),
+            window.transactions_per_second()
+        );
+        assert_eq!(
+            live.cu_per_second_for(CuMode::Inclusive),
+            window.cu_per_second_for(CuMode::Inclusive)
+        );
+        assert_eq!(
+            stats
+                .over(Duration::from_secs(60), now)
+                .cu_per_second_for(CuMode::Exclusive),
+            window.cu_per_second_for(CuMode::Exclusive)
+        );
     }
 
-    // Calculate time passed between oldest and newest timestamps
-    fn get_time_span(&self) -> f64 {
-        if self.slot_timeline.is_empty() {
-            return 1.0; // Default to 1 second to avoid division by zero
+    #[test]
+    fn test_rollups_cover_long_horizons() {
+        let start = Instant::now();
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+
+        // One slot every 30s for two hours (a slot is 400ms, so 75 slots apart)
+        for i in 0..240u64 {
+            stats.record_slot(SlotStats {
+                timestamp: start + Duration::from_secs(i * 30),
+                slot: 1_000_000 + i * 75,
+                block_time: Some(1_700_000_000 + i as i64 * 30),
+                ..slot(&[10_000 + i], &[])
+            });
         }
+        let now = ChainTime {
+            slot: 1_000_000 + 239 * 75,
+            block_time: Some(1_700_000_000 + 239 * 30),
+        };
+
+        // The slot window only has the last 5 minutes
+        assert_eq!(stats.window().total_transactions(), 11);
+        assert_eq!(
+            stats
+                .over(Duration::from_secs(60), now)
+                .total_transactions(),
+            2
+        );
 
-        if self.slot_timeline.len() == 1 {
-            return 1.0; // Single slot, use 1 second minimum
+        // 15 minutes and an hour come from per-minute buckets
+        let quarter = stats.over(Duration::from_secs(15 * 60), now);
+        assert!((30..=32).contains(&quarter.total_transactions()));
+        let hour = stats.over(Duration::from_secs(60 * 60), now);
+        assert!((120..=122).contains(&hour.total_transactions()));
+        assert!((hour.transactions_per_second() - 1.0 / 30.0).abs() < 0.002);
+
+        // A day comes from 10-minute buckets and sees everything
+        let day = stats.over(Duration::from_secs(24 * 60 * 60), now);
+        assert_eq!(day.total_transactions(), 240);
+        assert_eq!(day.min_cu_for(CuMode::Inclusive), 10_000);
+        assert_eq!(day.max_cu_for(CuMode::Inclusive), 10_239);
+        assert_eq!(day.cu_percentile_for(CuMode::Inclusive, 1.0), 10_239);
+    }
+
+    #[test]
+    fn test_rollup_memory_is_bounded() {
+        let start = Instant::now();
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+        let mut usage_after_a_day = 0;
+
+        // A slot every 4s for two days
+        for i in 0..43_200u64 {
+            stats.record_slot(SlotStats {
+                timestamp: start + Duration::from_secs(i * 4),
+                slot: i * 10,
+                ..slot(&[10_000 + i % 1_000], &["Slippage"])
+            });
+            if i == 21_600 {
+                usage_after_a_day = stats.memory_usage();
+            }
         }
 
-        // Get first and last elements without consuming iterator
-        let slots: Vec<_> = self.slot_timeline.iter().collect();
-        let first_slot = slots.first().unwrap();
-        let last_slot = slots.last().unwrap();
+        // Day two costs no more than day one did
+        assert!(stats.memory_usage() <= usage_after_a_day * 11 / 10);
+        let day = stats.over(Duration::from_secs(24 * 60 * 60), at_slot(432_000));
+        assert!(day.total_transactions() <= 21_600 + 150);
+    }
+
+    #[test]
+    fn test_history_splits_window_into_runs() {
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
 
-        let duration = last_slot.timestamp.duration_since(first_slot.timestamp);
-        let time_span = duration.as_secs_f64();
+        // 750 slots in the window, so 10 points of 75 slots (30s) each
+        stats.record_slot(SlotStats {
+            slot: 1_000,
+            ..slot(&[30_000, 60_000], &["Slippage"])
+        });
+        stats.record_slot(SlotStats {
+            slot: 1_001,
+            ..slot(&[30_000], &[])
+        });
+        stats.record_slot(SlotStats {
+            slot: 1_700,
+            ..slot(&[15_000], &[])
+        });
 
-        // Use minimum of 1 second to avoid infinity/huge numbers at startup
-        time_span.max(1.0)
+        let history = stats.history(10, WINDOW, CuMode::Inclusive, 1_700);
+        assert_eq!(history.len(), 10);
+
+        // Slots 951..=1025 fall in the first point, 1626..=1700 in the last
+        assert!((history[0].transactions_per_second - 3.0 / 30.0).abs() < 1e-9);
+        assert!((history[0].cu_per_second - 120_000.0 / 30.0).abs() < 1e-9);
+        assert!((history[0].success_rate.unwrap() - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(history[1], HistoryPoint::default());
+        assert_eq!(history[9].success_rate, Some(100.0));
+
+        let exclusive = stats.history(10, WINDOW, CuMode::Exclusive, 1_700);
+        assert!((exclusive[9].cu_per_second - 7_500.0 / 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_history_over_rollups() {
+        let mut stats = ProgramStats::new("JUP4Fb2c".to_string(), 750, WINDOW);
+
+        // One slot every 30s for two hours, as in test_rollups_cover_long_horizons
+        for i in 0..240u64 {
+            stats.record_slot(SlotStats {
+                slot: 1_000_000 + i * 75,
+                block_time: Some(1_700_000_000 + i as i64 * 30),
+                ..slot(&[10_000], &[])
+            });
+        }
+        let current_slot = 1_000_000 + 239 * 75;
+
+        // An hour asked for in 80 points comes back as 60 whole minutes
+        let history = stats.history(
+            80,
+            Duration::from_secs(60 * 60),
+            CuMode::Inclusive,
+            current_slot,
+        );
+        assert_eq!(history.len(), 60);
+        assert!((history[0].transactions_per_second - 2.0 / 60.0).abs() < 1e-9);
+        assert!((history[59].transactions_per_second - 1.0 / 60.0).abs() < 1e-9);
+        let total: f64 = history
+            .iter()
+            .map(|p| p.transactions_per_second * 60.0)
+            .sum();
+        assert!((total - 119.0).abs() < 1e-6);
     }
 }
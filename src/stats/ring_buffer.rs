@@ -1,11 +1,13 @@
+use std::collections::VecDeque;
+
 /// A fixed-size circular buffer that overwrites old data when full.
 ///
 /// Generic over type T - can store any data (numbers, structs, etc.)
+/// Items can also be dropped from the old end early, e.g. once they age out
+/// of a time window.
 pub struct RingBuffer<T> {
-    data: Vec<T>,
+    data: VecDeque<T>,
     capacity: usize,
-    head: usize,
-    count: usize,
 }
 
 impl<T> RingBuffer<T> {
@@ -13,45 +15,62 @@ impl<T> RingBuffer<T> {
     pub fn new(capacity: usize) -> Self {
         assert!(capacity > 0, "RingBuffer capacity must be greater than 0");
         Self {
-            data: Vec::with_capacity(capacity),
+            data: VecDeque::new(),
             capacity,
-            head: 0,
-            count: 0,
         }
     }
 
-    /// If full, overwrites the oldest item.
-    pub fn push(&mut self, item: T) {
-        if self.count < self.capacity {
-            self.data.push(item);
-            self.count += 1;
-        } else if self.head < self.capacity {
-            self.data[self.head] = item;
+    /// If full, overwrites the oldest item and returns it.
+    pub fn push(&mut self, item: T) -> Option<T> {
+        let evicted = if self.is_full() {
+            self.data.pop_front()
+        } else {
+            None
+        };
+        self.data.push_back(item);
+        evicted
+    }
+
+    /// Removes and returns the oldest item, if any.
+    ///
+    /// Gives memory back once the buffer is mostly empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        let item = self.data.pop_front();
+        if self.data.len() < self.data.capacity() / 4 {
+            self.data.shrink_to(self.data.len() * 2);
         }
-        // Move head forward, wrap around
-        self.head = (self.head + 1) % self.capacity;
+        item
+    }
+
+    /// Returns the oldest item, if any.
+    pub fn front(&self) -> Option<&T> {
+        self.data.front()
+    }
+
+    /// Returns the newest item, if any.
+    pub fn back(&self) -> Option<&T> {
+        self.data.back()
+    }
+
+    /// Returns the newest item mutably, if any.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.data.back_mut()
     }
 
     /// Returns an iterator over the buffer's items in insertion order.
     /// Oldest items first, newest items last.
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
-        if self.is_full() {
-            self.data[self.head..]
-                .iter()
-                .chain(self.data[..self.head].iter())
-        } else {
-            self.data[0..self.count].iter().chain([].iter())
-        }
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.data.iter()
     }
 
     /// Returns the current number of items in the buffer.
     pub fn len(&self) -> usize {
-        self.count
+        self.data.len()
     }
 
     /// Returns true if the buffer is empty.
     pub fn is_empty(&self) -> bool {
-        self.count == 0
+        self.data.is_empty()
     }
 
     /// Returns the maximum capacity.
@@ -59,17 +78,22 @@ impl<T> RingBuffer<T> {
         self.capacity
     }
 
+    /// Returns how many items there is memory for right now.
+    ///
+    /// Storage grows as items are pushed, up to `capacity`, rather than
+    /// being reserved up front.
+    pub fn allocated(&self) -> usize {
+        self.data.capacity()
+    }
+
     /// Returns true if the buffer is at capacity.
     pub fn is_full(&self) -> bool {
-        self.count == self.capacity
+        self.data.len() == self.capacity
     }
 
     /// Removes all items from the buffer.
     pub fn clear(&mut self) {
-        // TODO: Clear Vec and reset head/count
         self.data.clear();
-        self.count = 0;
-        self.head = 0;
     }
 }
 
@@ -145,6 +169,64 @@ mod tests {
         assert_eq!(iter, vec![&3, &4, &5]);
     }
 
+    #[test]
+    fn test_push_returns_evicted() {
+        let mut buffer: RingBuffer<i32> = RingBuffer::new(2);
+        assert_eq!(buffer.push(1), None);
+        assert_eq!(buffer.push(2), None);
+        assert_eq!(buffer.push(3), Some(1));
+        assert_eq!(buffer.push(4), Some(2));
+    }
+
+    #[test]
+    fn test_front_and_back() {
+        let mut buffer: RingBuffer<i32> = RingBuffer::new(3);
+        assert_eq!(buffer.front(), None);
+        assert_eq!(buffer.back(), None);
+
+        buffer.push(1);
+        buffer.push(2);
+        assert_eq!(buffer.front(), Some(&1));
+        assert_eq!(buffer.back(), Some(&2));
+
+        buffer.push(3);
+        buffer.push(4);
+        assert_eq!(buffer.front(), Some(&2));
+        assert_eq!(buffer.back(), Some(&4));
+    }
+
+    #[test]
+    fn test_pop_front_then_push() {
+        let mut buffer: RingBuffer<i32> = RingBuffer::new(3);
+        buffer.push(1);
+        buffer.push(2);
+        buffer.push(3);
+
+        assert_eq!(buffer.pop_front(), Some(1));
+        assert!(!buffer.is_full());
+        assert_eq!(buffer.push(4), None);
+        assert_eq!(buffer.push(5), Some(2));
+
+        let items: Vec<_> = buffer.iter().collect();
+        assert_eq!(items, vec![&3, &4, &5]);
+    }
+
+    #[test]
+    fn test_memory_follows_contents() {
+        let mut buffer: RingBuffer<u64> = RingBuffer::new(1_000);
+        assert_eq!(buffer.allocated(), 0);
+
+        for i in 0..500 {
+            buffer.push(i);
+        }
+        assert!(buffer.allocated() >= 500);
+
+        while buffer.len() > 10 {
+            buffer.pop_front();
+        }
+        assert!(buffer.allocated() < 100);
+    }
+
     #[test]
     fn test_clear() {
         let mut buffer = RingBuffer::new(3);
@@ -0,0 +1,224 @@
+use super::program::{ChainTime, SlotStats, SlotTotals, StatsView, SLOT_DURATION};
+use super::RingBuffer;
+use std::borrow::Cow;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+/// Slots merged into one bucket
+struct Bucket {
+    /// Which run of slots this is (`slot / slots_per_bucket`)
+    index: u64,
+
+    /// Sum of every slot in the bucket
+    ///
+    /// `slot` and `block_time` are the first slot's, `timestamp` the latest.
+    stats: SlotStats,
+
+    /// Last slot merged in, and its block time
+    last_slot: u64,
+    last_block_time: Option<i64>,
+}
+
+impl Bucket {
+    /// Where the last slot merged in sits on the chain
+    fn last_chain_time(&self) -> ChainTime {
+        ChainTime {
+            slot: self.last_slot,
+            block_time: self.last_block_time,
+        }
+    }
+}
+
+/// One resolution of a program's history
+///
+/// Slots go into fixed-size buckets (or one bucket per slot for the finest
+/// tier), kept for `retention` and at most `capacity` buckets. Running totals
+/// over all buckets are maintained as they enter and leave, like the window
+/// in `ProgramStats`, so the whole tier reads in O(1).
+pub(super) struct Tier {
+    /// Slots per bucket; None keeps every recorded slot separately
+    slots_per_bucket: Option<u64>,
+
+    /// How much chain time buckets are kept after their last slot
+    retention: Duration,
+
+    buckets: RingBuffer<Bucket>,
+    totals: SlotTotals,
+}
+
+impl Tier {
+    /// A tier keeping every slot, up to `capacity` of them
+    pub(super) fn per_slot(capacity: usize, retention: Duration) -> Self {
+        Self::with_buckets(None, capacity, retention)
+    }
+
+    /// A tier merging slots into `bucket`-long buckets
+    pub(super) fn rollup(bucket: Duration, retention: Duration) -> Self {
+        let slots_per_bucket = (bucket.as_millis() / SLOT_DURATION.as_millis()).max(1) as u64;
+        // One more for the bucket still filling up
+        let capacity = (retention.as_millis() / bucket.as_millis()) as usize + 1;
+        Self::with_buckets(Some(slots_per_bucket), capacity, retention)
+    }
+
+    fn with_buckets(slots_per_bucket: Option<u64>, capacity: usize, retention: Duration) -> Self {
+        Self {
+            slots_per_bucket,
+            retention,
+            buckets: RingBuffer::new(capacity),
+            totals: SlotTotals::new(),
+        }
+    }
+
+    /// How far back this tier reaches
+    pub(super) fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    /// Add a slot, merging it into the current bucket if it belongs there
+    pub(super) fn record(&mut self, slot: SlotStats) {
+        let index = self.slots_per_bucket.map(|n| slot.slot / n);
+
+        if let (Some(index), Some(current)) = (index, self.buckets.back_mut()) {
+            if index <= current.index {
+                self.totals.extend(&slot);
+                current.last_slot = current.last_slot.max(slot.slot);
+                current.last_block_time = slot.block_time.or(current.last_block_time);
+                current.stats.merge(&slot);
+                return;
+            }
+        }
+
+        self.totals.add(&slot);
+        let bucket = Bucket {
+            index: index.unwrap_or(slot.slot),
+            last_slot: slot.slot,
+            last_block_time: slot.block_time,
+            stats: slot,
+        };
+        if let Some(evicted) = self.buckets.push(bucket) {
+            // The buffer now holds the newest `len` buckets; the evicted one came right before
+            let evicted_seq = self.oldest_seq() - 1;
+            self.totals.remove(evicted_seq, &evicted.stats);
+        }
+    }
+
+    /// Evict buckets whose last slot is more than `retention` of chain time
+    /// before `now`
+    pub(super) fn expire(&mut self, now: ChainTime) {
+        while let Some(oldest) = self.buckets.front() {
+            if now.since(oldest.last_chain_time()) <= self.retention {
+                break;
+            }
+            let seq = self.oldest_seq();
+            if let Some(evicted) = self.buckets.pop_front() {
+                self.totals.remove(seq, &evicted.stats);
+            }
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// When the newest bucket was last updated
+    pub(super) fn last_seen(&self) -> Option<Instant> {
+        self.buckets.back().map(|b| b.stats.timestamp)
+    }
+
+    /// Approximate bytes held, beyond the struct itself
+    pub(super) fn heap_size(&self) -> usize {
+        self.buckets.allocated() * size_of::<Bucket>()
+            + self
+                .buckets
+                .iter()
+                .map(|b| b.stats.heap_size())
+                .sum::<usize>()
+            + self.totals.heap_size()
+    }
+
+    /// Running totals over every bucket
+    pub(super) fn totals(&self) -> &SlotTotals {
+        &self.totals
+    }
+
+    /// Metrics over every bucket
+    pub(super) fn whole(&self) -> StatsView<'_> {
+        StatsView::new(Cow::Borrowed(&self.totals), self.time_span())
+    }
+
+    /// Metrics over the last `horizon` of chain time, up to `now`
+    ///
+    /// If the whole tier falls inside the horizon, the running totals are used
+    /// as is; otherwise only the buckets inside it are walked, whole buckets
+    /// at a time. Rates are per second of the horizon, or of the chain time
+    /// since the oldest bucket if that is shorter, measured like `whole()`:
+    /// blockTime when both ends have it, otherwise 400ms per slot.
+    pub(super) fn view(&self, horizon: Duration, now: ChainTime) -> StatsView<'_> {
+        let Some(oldest) = self.buckets.front() else {
+            return self.whole();
+        };
+        let covered = now.since(oldest.stats.chain_time());
+        if covered < horizon && horizon >= self.retention {
+            return self.whole();
+        }
+
+        let mut totals = SlotTotals::new();
+        for bucket in self
+            .buckets
+            .iter()
+            .rev()
+            .take_while(|b| now.since(b.last_chain_time()) < horizon)
+        {
+            totals.add(&bucket.stats);
+        }
+
+        let time_span = horizon.min(covered).as_secs_f64();
+        StatsView::new(Cow::Owned(totals), time_span.max(1.0))
+    }
+
+    /// Slots each bucket covers (1 for the per-slot tier)
+    pub(super) fn bucket_slots(&self) -> u64 {
+        self.slots_per_bucket.unwrap_or(1)
+    }
+
+    /// Iterate the per-bucket stats with the first slot each bucket covers,
+    /// oldest first
+    pub(super) fn iter_from(&self) -> impl DoubleEndedIterator<Item = (u64, &SlotStats)> {
+        let bucket_slots = self.bucket_slots();
+        self.buckets
+            .iter()
+            .map(move |b| (b.index * bucket_slots, &b.stats))
+    }
+
+    /// Iterate the per-bucket stats, oldest first
+    pub(super) fn iter(&self) -> impl DoubleEndedIterator<Item = &SlotStats> {
+        self.buckets.iter().map(|b| &b.stats)
+    }
+
+    /// Sequence number of the oldest bucket in the buffer
+    fn oldest_seq(&self) -> u64 {
+        self.totals.next_seq() - self.buckets.len() as u64
+    }
+
+    // Calculate chain time passed between the oldest and newest slots
+    //
+    // Uses blockTime when both ends have it, otherwise counts slots. Processing
+    // time would be wrong while catching up: a burst of backfilled blocks
+    // squeezes minutes of chain time into milliseconds.
+    fn time_span(&self) -> f64 {
+        let (Some(first), Some(last)) = (self.buckets.front(), self.buckets.back()) else {
+            return 1.0; // Default to 1 second to avoid division by zero
+        };
+
+        let time_span = match (first.stats.block_time, last.last_block_time) {
+            (Some(start), Some(end)) if end > start => (end - start) as f64,
+            _ => {
+                last.last_slot.saturating_sub(first.stats.slot) as f64 * SLOT_DURATION.as_secs_f64()
+            }
+        };
+
+        // Use minimum of 1 second to avoid infinity/huge numbers at startup
+        // (including a single slot, where the span is zero)
+        time_span.max(1.0)
+    }
+}
@@ -0,0 +1,276 @@
+/// Growth factor between bucket boundaries: quantiles are within ±1%
+const GAMMA: f64 = 1.02;
+
+/// Mergeable quantile sketch for CU values (log-bucketed, DDSketch-style)
+///
+/// Each value lands in bucket `ceil(log_gamma(value))`, so every bucket spans
+/// a fixed ratio and any quantile comes back within 1% of the true value.
+/// Buckets are kept sparse and sorted, which keeps per-slot sketches tiny and
+/// lets a whole window be merged bucket by bucket.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CuSketch {
+    /// (bucket index, count), sorted by index
+    buckets: Vec<(u16, u32)>,
+
+    /// Values equal to 0 (log is undefined there)
+    zero_count: u32,
+
+    count: u64,
+    min: u64,
+    max: u64,
+}
+
+impl CuSketch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one value
+    pub fn insert(&mut self, value: u64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+
+        if value == 0 {
+            self.zero_count += 1;
+            return;
+        }
+
+        let index = bucket_index(value);
+        match self.buckets.binary_search_by_key(&index, |&(i, _)| i) {
+            Ok(pos) => self.buckets[pos].1 += 1,
+            Err(pos) => self.buckets.insert(pos, (index, 1)),
+        }
+    }
+
+    /// Fold another sketch into this one
+    pub fn merge(&mut self, other: &CuSketch) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+
+        let mut merged = Vec::with_capacity(self.buckets.len() + other.buckets.len());
+        let (mut a, mut b) = (
+            self.buckets.iter().peekable(),
+            other.buckets.iter().peekable(),
+        );
+        loop {
+            let next = match (a.peek(), b.peek()) {
+                (Some(&&(ia, ca)), Some(&&(ib, cb))) if ia == ib => {
+                    a.next();
+                    b.next();
+                    (ia, ca + cb)
+                }
+                (Some(&&x), Some(&&y)) => {
+                    if x.0 < y.0 {
+                        a.next();
+                        x
+                    } else {
+                        b.next();
+                        y
+                    }
+                }
+                (Some(&&x), None) => {
+                    a.next();
+                    x
+                }
+                (None, Some(&&y)) => {
+                    b.next();
+                    y
+                }
+                (None, None) => break,
+            };
+            merged.push(next);
+        }
+
+        self.buckets = merged;
+        self.zero_count += other.zero_count;
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Take a previously merged sketch back out of this one
+    ///
+    /// Buckets can't tell the exact extremes of what remains, so the caller
+    /// passes them in as `min` and `max`.
+    pub fn remove(&mut self, other: &CuSketch, min: u64, max: u64) {
+        for &(index, count) in &other.buckets {
+            if let Ok(pos) = self.buckets.binary_search_by_key(&index, |&(i, _)| i) {
+                let left = self.buckets[pos].1.saturating_sub(count);
+                if left == 0 {
+                    self.buckets.remove(pos);
+                } else {
+                    self.buckets[pos].1 = left;
+                }
+            }
+        }
+        self.zero_count = self.zero_count.saturating_sub(other.zero_count);
+        self.count = self.count.saturating_sub(other.count);
+
+        if self.count == 0 {
+            *self = CuSketch::default();
+        } else {
+            self.min = min;
+            self.max = max;
+        }
+    }
+
+    /// Approximate heap bytes held by the buckets
+    pub fn heap_size(&self) -> usize {
+        self.buckets.capacity() * std::mem::size_of::<(u16, u32)>()
+    }
+
+    /// Number of values added
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Smallest value added (exact), or 0 if empty
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    /// Largest value added (exact), or 0 if empty
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Value at quantile `q` (0.0 to 1.0), or 0 if the sketch is empty
+    ///
+    /// Exact for the minimum and maximum, within 1% in between.
+    pub fn quantile(&self, q: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+
+        // Rank of the wanted value (0-based)
+        let rank = (q.clamp(0.0, 1.0) * (self.count - 1) as f64).round() as u64;
+        if rank == 0 {
+            return self.min;
+        }
+        if rank == self.count - 1 {
+            return self.max;
+        }
+        if rank < self.zero_count as u64 {
+            return 0;
+        }
+
+        let mut seen = self.zero_count as u64;
+        for &(index, count) in &self.buckets {
+            seen += count as u64;
+            if seen > rank {
+                return bucket_value(index).clamp(self.min, self.max);
+            }
+        }
+
+        self.max
+    }
+}
+
+/// Bucket holding `value` (> 0): `ceil(log_gamma(value))`
+fn bucket_index(value: u64) -> u16 {
+    ((value as f64).ln() / GAMMA.ln()).ceil() as u16
+}
+
+/// Representative value of a bucket: the point with equal relative error to both bounds
+fn bucket_value(index: u16) -> u64 {
+    let upper = GAMMA.powi(index as i32);
+    (2.0 * upper / (GAMMA + 1.0)).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn within_one_percent(actual: u64, expected: u64) -> bool {
+        (actual as f64 - expected as f64).abs() <= expected as f64 * 0.01
+    }
+
+    #[test]
+    fn test_quantiles_within_error_bound() {
+        let mut sketch = CuSketch::new();
+        for cu in 1..=10_000 {
+            sketch.insert(cu * 100);
+        }
+
+        assert_eq!(sketch.count(), 10_000);
+        assert!(within_one_percent(sketch.quantile(0.5), 500_000));
+        assert!(within_one_percent(sketch.quantile(0.9), 900_000));
+        assert!(within_one_percent(sketch.quantile(0.99), 990_000));
+        assert_eq!(sketch.quantile(0.0), 100);
+        assert_eq!(sketch.quantile(1.0), 1_000_000);
+    }
+
+    #[test]
+    fn test_merge_matches_single_sketch() {
+        let mut whole = CuSketch::new();
+        let mut parts = vec![CuSketch::new(), CuSketch::new(), CuSketch::new()];
+        for cu in 0..3_000u64 {
+            let value = (cu * 7919) % 200_000;
+            whole.insert(value);
+            parts[(cu % 3) as usize].insert(value);
+        }
+
+        let mut merged = CuSketch::new();
+        for part in &parts {
+            merged.merge(part);
+        }
+
+        assert_eq!(merged, whole);
+    }
+
+    #[test]
+    fn test_remove_undoes_merge() {
+        let mut old = CuSketch::new();
+        old.insert(0);
+        old.insert(1_000);
+        let mut new = CuSketch::new();
+        new.insert(5_000);
+        new.insert(9_000);
+
+        let mut window = CuSketch::new();
+        window.merge(&old);
+        window.merge(&new);
+        window.remove(&old, 5_000, 9_000);
+
+        assert_eq!(window, new);
+
+        window.remove(&new, 0, 0);
+        assert_eq!(window, CuSketch::new());
+    }
+
+    #[test]
+    fn test_tail_shows_up() {
+        // 99 cheap transactions and one expensive one
+        let mut sketch = CuSketch::new();
+        for _ in 0..99 {
+            sketch.insert(5_000);
+        }
+        sketch.insert(1_400_000);
+
+        assert!(within_one_percent(sketch.quantile(0.5), 5_000));
+        assert_eq!(sketch.quantile(1.0), 1_400_000);
+    }
+
+    #[test]
+    fn test_empty_and_zero() {
+        assert_eq!(CuSketch::new().quantile(0.5), 0);
+
+        let mut sketch = CuSketch::new();
+        sketch.insert(0);
+        sketch.insert(0);
+        sketch.insert(10_000);
+        assert_eq!(sketch.quantile(0.5), 0);
+        assert_eq!(sketch.quantile(1.0), 10_000);
+    }
+}
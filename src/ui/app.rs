@@ -1,5 +1,9 @@
-use super::Theme;
-use crate::stats::{is_system_program, NetworkState};
+use super::{Filter, Theme};
+use crate::rpc::EndpointStatus;
+use crate::stats::{
+    is_system_program, program_label, ChainTime, CuMode, HistoryPoint, NetworkState, ProgramStats,
+    StatsView,
+};
 use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode};
 use ratatui::{
@@ -7,19 +11,151 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::Style,
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    widgets::{
+        Bar, BarChart, BarGroup, Block, Borders, Cell, Paragraph, Row, Sparkline, Table, TableState,
+    },
     Frame, Terminal,
 };
-use std::cmp::Reverse;
+use std::cmp::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 
+/// Errors listed for the selected program
+const ERROR_PANEL_ROWS: usize = 5;
+
+/// Default horizon for Live mode
+const DEFAULT_LIVE_HORIZON: Duration = Duration::from_secs(10);
+
+/// Quantiles shown in the detail pane's CU distribution
+const DISTRIBUTION_QUANTILES: [(&str, f64); 7] = [
+    ("min", 0.0),
+    ("p25", 0.25),
+    ("p50", 0.5),
+    ("p75", 0.75),
+    ("p90", 0.9),
+    ("p99", 0.99),
+    ("max", 1.0),
+];
+
+/// Longer horizons Window mode cycles through (after the slot window itself)
+const WINDOW_HORIZONS: [Duration; 4] = [
+    Duration::from_secs(60),
+    Duration::from_secs(15 * 60),
+    Duration::from_secs(60 * 60),
+    Duration::from_secs(24 * 60 * 60),
+];
+
 /// View mode for displaying statistics
 #[derive(Clone, Copy, PartialEq)]
 enum ViewMode {
-    Live,   // Current behavior - shows recent activity
-    Window, // Shows aggregate stats for entire window
+    Live,   // Activity over the last few seconds (`live_horizon`)
+    Window, // Aggregate stats over the window or a chosen horizon
+}
+
+/// Table column the programs are sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    ProgramId,
+    Tps,
+    CuPerSec,
+    AvgCu,
+    MinCu,
+    P50Cu,
+    P90Cu,
+    P99Cu,
+    MaxCu,
+    Total,
+    Calls,
+    Success,
+    SelfFail,
+}
+
+impl SortColumn {
+    /// In table order
+    const ALL: [SortColumn; 13] = [
+        SortColumn::ProgramId,
+        SortColumn::Tps,
+        SortColumn::CuPerSec,
+        SortColumn::AvgCu,
+        SortColumn::MinCu,
+        SortColumn::P50Cu,
+        SortColumn::P90Cu,
+        SortColumn::P99Cu,
+        SortColumn::MaxCu,
+        SortColumn::Total,
+        SortColumn::Calls,
+        SortColumn::Success,
+        SortColumn::SelfFail,
+    ];
+
+    /// Column header
+    fn title(self) -> &'static str {
+        match self {
+            SortColumn::ProgramId => "Program ID",
+            SortColumn::Tps => "Txs/s",
+            SortColumn::CuPerSec => "CU/s",
+            SortColumn::AvgCu => "Avg CU",
+            SortColumn::MinCu => "Min CU",
+            SortColumn::P50Cu => "p50",
+            SortColumn::P90Cu => "p90",
+            SortColumn::P99Cu => "p99",
+            SortColumn::MaxCu => "Max CU",
+            SortColumn::Total => "Total",
+            SortColumn::Calls => "Calls",
+            SortColumn::Success => "Success%",
+            SortColumn::SelfFail => "Self Fail%",
+        }
+    }
+
+    /// The column `offset` places to the right, wrapping around
+    fn shift(self, offset: isize) -> Self {
+        let len = Self::ALL.len() as isize;
+        let idx = Self::ALL.iter().position(|&c| c == self).unwrap_or(0) as isize;
+        Self::ALL[(idx + offset).rem_euclid(len) as usize]
+    }
+
+    /// Ascending order of two rows by this column
+    fn compare(self, a: &ProgramStatsDisplay, b: &ProgramStatsDisplay) -> Ordering {
+        match self {
+            SortColumn::ProgramId => a.program_id.cmp(&b.program_id),
+            SortColumn::Tps => a.tx_per_sec.total_cmp(&b.tx_per_sec),
+            SortColumn::CuPerSec => a.cu_per_sec.total_cmp(&b.cu_per_sec),
+            SortColumn::AvgCu => a.avg_cu.total_cmp(&b.avg_cu),
+            SortColumn::MinCu => a.min_cu.cmp(&b.min_cu),
+            SortColumn::P50Cu => a.p50_cu.cmp(&b.p50_cu),
+            SortColumn::P90Cu => a.p90_cu.cmp(&b.p90_cu),
+            SortColumn::P99Cu => a.p99_cu.cmp(&b.p99_cu),
+            SortColumn::MaxCu => a.max_cu.cmp(&b.max_cu),
+            SortColumn::Total => a.total_txs.cmp(&b.total_txs),
+            SortColumn::Calls => a.total_invocations.cmp(&b.total_invocations),
+            SortColumn::Success => a.success_rate.total_cmp(&b.success_rate),
+            SortColumn::SelfFail => a.program_failure_rate.total_cmp(&b.program_failure_rate),
+        }
+    }
+}
+
+/// Whether a program's ID or known label contains `query` (ignoring case)
+///
+/// An empty query matches everything.
+fn matches_search(program_id: &str, query: &str) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+
+    let query = query.to_lowercase();
+    program_id.to_lowercase().contains(&query)
+        || program_label(program_id).is_some_and(|label| label.to_lowercase().contains(&query))
+}
+
+/// Sort rows by `column`, ties broken by program ID so the order is stable
+/// between refreshes
+fn sort_programs(rows: &mut [ProgramStatsDisplay], column: SortColumn, descending: bool) {
+    rows.sort_by(|a, b| {
+        let order = column.compare(a, b);
+        let order = if descending { order.reverse() } else { order };
+        order.then_with(|| a.program_id.cmp(&b.program_id))
+    });
 }
 
 /// Main TUI application
@@ -30,8 +166,22 @@ pub struct App {
     /// Whether the app should keep running
     pub running: bool,
 
-    /// Currently selected row in the table
-    pub selected_row: usize,
+    /// Selected row and scroll offset of the program table
+    table_state: TableState,
+
+    /// Program the selection follows when the table re-sorts
+    selected_program: Option<String>,
+
+    /// Program rows that fit in the table (for PgUp/PgDn), as last drawn
+    page_size: usize,
+
+    /// Program shown in the detail pane (open with Enter, close with Esc)
+    detail: Option<String>,
+
+    cached_detail: Option<ProgramDetail>,
+
+    /// Columns available to the detail charts, as last drawn
+    chart_width: usize,
 
     cached_stats: Vec<ProgramStatsDisplay>,
 
@@ -46,9 +196,40 @@ pub struct App {
     /// Whether to hide system programs (toggle with 'u')
     hide_system_programs: bool,
 
+    /// Only programs whose ID or label contains this (edit with '/')
+    search: String,
+
+    /// Whether the search prompt has the keyboard
+    searching: bool,
+
+    /// Only programs passing this metric filter (edit with '\' or --filter)
+    filter: Option<Filter>,
+
+    /// Filter being edited, while the filter prompt has the keyboard
+    filter_input: Option<String>,
+
+    /// Why the edited filter didn't parse
+    filter_error: Option<String>,
+
     /// Current view mode (toggle with 'w')
     view_mode: ViewMode,
 
+    /// How far back Live mode looks
+    live_horizon: Duration,
+
+    /// Index into WINDOW_HORIZONS for Window mode (cycle with 'h'); None
+    /// shows the whole slot window
+    window_horizon: Option<usize>,
+
+    /// Column the table is sorted by (change with F6 or '<' / '>')
+    sort_column: SortColumn,
+
+    /// Largest first (reverse with 'r')
+    sort_descending: bool,
+
+    /// Inclusive or exclusive (self) CU in the table (toggle with 'x')
+    cu_mode: CuMode,
+
     /// Loading state - true until first data arrives
     loading: bool,
 }
@@ -59,7 +240,12 @@ impl App {
         Self {
             network_state,
             running: true,
-            selected_row: 0,
+            table_state: TableState::default(),
+            selected_program: None,
+            page_size: 1,
+            detail: None,
+            cached_detail: None,
+            chart_width: 60,
             cached_stats: vec![],
             cached_network_stats: NetworkStatsDisplay {
                 current_slot: 0,
@@ -71,20 +257,52 @@ impl App {
                 total_txs: 0,
                 avg_success_rate: 0.0,
                 total_cu_per_sec: 0.0,
+                versioned_share: 0.0,
+                endpoints: Vec::new(),
+                websocket_connected: None,
+                blocks_in_flight: 0,
+                fetch_concurrency: 0,
             },
             theme: Theme::flatline(),
             truncate_ids: false,
             hide_system_programs: false,
+            search: String::new(),
+            searching: false,
+            filter: None,
+            filter_input: None,
+            filter_error: None,
             view_mode: ViewMode::Live,
+            live_horizon: DEFAULT_LIVE_HORIZON,
+            window_horizon: None,
+            sort_column: SortColumn::Total,
+            sort_descending: true,
+            cu_mode: CuMode::Inclusive,
             loading: true,
         }
     }
 
+    /// Set how far back Live mode looks (default 10s)
+    pub fn with_live_horizon(mut self, horizon: Duration) -> Self {
+        self.live_horizon = horizon;
+        self
+    }
+
+    /// Only show programs passing `filter`
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Update cached stats from network state
     async fn update_stats(&mut self) {
         let (program_stats, network_stats) = self.get_stats().await;
         self.cached_stats = program_stats;
         self.cached_network_stats = network_stats;
+        self.restore_selection();
+        self.cached_detail = match &self.detail {
+            Some(program_id) => self.get_detail(program_id).await,
+            None => None,
+        };
 
         // Exit loading state once we have data
         if self.cached_network_stats.current_slot > 0 {
@@ -97,6 +315,45 @@ impl App {
         &self.cached_stats
     }
 
+    /// Stats of the selected program, if any
+    fn selected_stats(&self) -> Option<&ProgramStatsDisplay> {
+        self.table_state
+            .selected()
+            .and_then(|idx| self.cached_stats.get(idx))
+    }
+
+    /// Select a row (clamped to the table) and remember its program
+    fn select(&mut self, idx: usize) {
+        if self.cached_stats.is_empty() {
+            self.table_state.select(None);
+            self.selected_program = None;
+            return;
+        }
+
+        let idx = idx.min(self.cached_stats.len() - 1);
+        self.table_state.select(Some(idx));
+        self.selected_program = Some(self.cached_stats[idx].program_id.clone());
+    }
+
+    /// Move the selection by `delta` rows
+    fn move_selection(&mut self, delta: isize) {
+        let current = self.table_state.selected().unwrap_or(0);
+        self.select(current.saturating_add_signed(delta));
+    }
+
+    /// Keep the selection on the same program after the stats are refreshed
+    ///
+    /// If the program left the table, the selection stays at the same row.
+    fn restore_selection(&mut self) {
+        let followed = self.selected_program.as_ref().and_then(|selected| {
+            self.cached_stats
+                .iter()
+                .position(|stat| &stat.program_id == selected)
+        });
+        let idx = followed.or(self.table_state.selected()).unwrap_or(0);
+        self.select(idx);
+    }
+
     /// Run the main event loop
     pub async fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
         // Tick rate: how often we update the UI
@@ -140,7 +397,7 @@ impl App {
     }
 
     /// Render the entire UI
-    pub fn render(&self, frame: &mut Frame) {
+    pub fn render(&mut self, frame: &mut Frame) {
         let area = frame.area();
 
         // Show loading screen if no data yet
@@ -149,14 +406,34 @@ impl App {
             return;
         }
 
-        // Create main layout: header + network overview + table + footer
+        // The detail pane takes the place of the table and error panel
+        if self.detail.is_some() {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(5), // Header
+                    Constraint::Length(3), // Network Overview
+                    Constraint::Min(20),   // Detail of the chosen program
+                    Constraint::Length(1), // Footer
+                ])
+                .split(area);
+
+            self.render_header(frame, chunks[0]);
+            self.render_network_overview(frame, chunks[1]);
+            self.render_detail(frame, chunks[2]);
+            self.render_footer(frame, chunks[3]);
+            return;
+        }
+
+        // Create main layout: header + network overview + table + errors + footer
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(5), // Header (normal size)
-                Constraint::Length(3), // Network Overview
-                Constraint::Min(10),   // Table (takes remaining space)
-                Constraint::Length(1), // Footer
+                Constraint::Length(5),                           // Header (normal size)
+                Constraint::Length(3),                           // Network Overview
+                Constraint::Min(10),                             // Table (takes remaining space)
+                Constraint::Length(ERROR_PANEL_ROWS as u16 + 2), // Errors of selected program
+                Constraint::Length(1),                           // Footer
             ])
             .split(area);
 
@@ -164,7 +441,8 @@ impl App {
         self.render_header(frame, chunks[0]);
         self.render_network_overview(frame, chunks[1]);
         self.render_table(frame, chunks[2]);
-        self.render_footer(frame, chunks[3]);
+        self.render_error_panel(frame, chunks[3]);
+        self.render_footer(frame, chunks[4]);
     }
 
     /// Render the loading screen with logo
@@ -233,7 +511,7 @@ impl App {
             .constraints([
                 Constraint::Length(1), // Line 1: Slot
                 Constraint::Length(1), // Line 2: Stats with mode indicators
-                Constraint::Length(1), // Line 3: Spacer
+                Constraint::Length(1), // Line 3: RPC endpoint health
             ])
             .split(inner);
 
@@ -241,10 +519,12 @@ impl App {
         let lag = stats.latest_network_slot.saturating_sub(stats.current_slot);
 
         let slot_text = Paragraph::new(format!(
-            "Slot: {} │ Network: {} ({} behind)",
+            "Slot: {} │ Network: {} ({} behind) │ Fetching: {}/{}",
             format_large_number(stats.current_slot),
             format_large_number(stats.latest_network_slot),
-            lag
+            lag,
+            stats.blocks_in_flight,
+            stats.fetch_concurrency
         ))
         .style(self.theme.normal_style());
         frame.render_widget(slot_text, info_chunks[0]);
@@ -257,15 +537,21 @@ impl App {
         ];
 
         // Add mode indicators
-        let mut indicators = Vec::new();
+        let mut indicators = vec![format!("[{}]", self.mode_label())];
         if self.truncate_ids {
-            indicators.push("[TRUNCATED]");
+            indicators.push("[TRUNCATED]".to_string());
         }
         if self.hide_system_programs {
-            indicators.push("[FILTERED]");
+            indicators.push("[FILTERED]".to_string());
         }
-        if self.view_mode == ViewMode::Window {
-            indicators.push("[WINDOW VIEW]");
+        if !self.search.is_empty() {
+            indicators.push(format!("[SEARCH: {}]", self.search));
+        }
+        if let Some(filter) = &self.filter {
+            indicators.push(format!("[FILTER: {}]", filter));
+        }
+        if self.cu_mode == CuMode::Exclusive {
+            indicators.push("[SELF CU]".to_string());
         }
 
         if !indicators.is_empty() {
@@ -274,6 +560,65 @@ impl App {
 
         let stats_text = Paragraph::new(status_parts.join(" │ ")).style(self.theme.muted_style());
         frame.render_widget(stats_text, info_chunks[1]);
+
+        // Line 3: Active RPC endpoint and its health
+        let rpc_text = Paragraph::new(Line::from(
+            self.endpoint_spans(&stats.endpoints, stats.websocket_connected),
+        ));
+        frame.render_widget(rpc_text, info_chunks[2]);
+    }
+
+    /// Build the header spans describing the active RPC endpoint
+    fn endpoint_spans(
+        &self,
+        endpoints: &[EndpointStatus],
+        websocket_connected: Option<bool>,
+    ) -> Vec<Span<'static>> {
+        let Some(active) = endpoints.iter().find(|e| e.active) else {
+            return vec![Span::styled("RPC: connecting...", self.theme.muted_style())];
+        };
+
+        let status_color = if !active.healthy {
+            self.theme.error
+        } else if active.error_rate >= 0.1 || active.slots_behind > 0 {
+            self.theme.amber
+        } else {
+            self.theme.success
+        };
+
+        let latency = active
+            .latency_ms
+            .map(|ms| format!("{:.0}ms", ms))
+            .unwrap_or_else(|| "-".to_string());
+
+        let mut spans = vec![
+            Span::styled("RPC: ", self.theme.muted_style()),
+            Span::styled(endpoint_host(&active.url), self.theme.normal_style()),
+            Span::styled(" ● ", Style::default().fg(status_color)),
+            Span::styled(
+                format!("{} │ err {:.1}%", latency, active.error_rate * 100.0),
+                self.theme.muted_style(),
+            ),
+        ];
+
+        if endpoints.len() > 1 {
+            let healthy = endpoints.iter().filter(|e| e.healthy).count();
+            spans.push(Span::styled(
+                format!(" │ {}/{} endpoints healthy", healthy, endpoints.len()),
+                self.theme.muted_style(),
+            ));
+        }
+
+        match websocket_connected {
+            Some(true) => spans.push(Span::styled(" │ WS", self.theme.success_style())),
+            Some(false) => spans.push(Span::styled(
+                " │ WS down, polling",
+                self.theme.warning_style(),
+            )),
+            None => {}
+        }
+
+        spans
     }
 
     /// Render the network overview panel
@@ -314,6 +659,12 @@ impl App {
                 format_cu(stats.total_cu_per_sec),
                 Style::default().fg(self.theme.cu_per_sec_color(stats.total_cu_per_sec)),
             ),
+            Span::raw("  │  "),
+            Span::styled("v0 Txs: ", self.theme.muted_style()),
+            Span::styled(
+                format!("{:.1}%", stats.versioned_share),
+                self.theme.normal_style(),
+            ),
         ];
 
         let overview_text = Paragraph::new(Line::from(spans)).alignment(Alignment::Center);
@@ -322,18 +673,16 @@ impl App {
     }
 
     /// Render the statistics table
-    fn render_table(&self, frame: &mut Frame, area: Rect) {
-        // Table header with neon green
-        let header = Row::new(vec![
-            Cell::from("Program ID"),
-            Cell::from("Txs/s"),
-            Cell::from("CU/s"),
-            Cell::from("Avg CU"),
-            Cell::from("Min CU"),
-            Cell::from("Max CU"),
-            Cell::from("Total"),
-            Cell::from("Success%"),
-        ])
+    fn render_table(&mut self, frame: &mut Frame, area: Rect) {
+        // Table header with neon green, the sort column marked with its direction
+        let header = Row::new(SortColumn::ALL.map(|column| {
+            if column == self.sort_column {
+                let arrow = if self.sort_descending { "▼" } else { "▲" };
+                Cell::from(format!("{} {}", column.title(), arrow))
+            } else {
+                Cell::from(column.title())
+            }
+        }))
         .style(self.theme.table_header_style())
         .height(1);
 
@@ -345,6 +694,9 @@ impl App {
                 // Color code based on metrics
                 let tps_color = self.theme.tps_color(stat.tx_per_sec);
                 let success_color = self.theme.success_rate_color(stat.success_rate);
+                let self_fail_color = self
+                    .theme
+                    .success_rate_color(100.0 - stat.program_failure_rate);
                 let cu_per_sec_color = self.theme.cu_per_sec_color(stat.cu_per_sec);
                 let avg_cu_color = self.theme.avg_cu_color(stat.avg_cu);
 
@@ -368,13 +720,24 @@ impl App {
                     Cell::from(format_cu(stat.avg_cu)).style(Style::default().fg(avg_cu_color)),
                     // Min CU
                     Cell::from(format_cu(stat.min_cu as f64)).style(self.theme.normal_style()),
+                    // Percentiles (p99 color coded like Avg CU: the tail that hurts)
+                    Cell::from(format_cu(stat.p50_cu as f64)).style(self.theme.normal_style()),
+                    Cell::from(format_cu(stat.p90_cu as f64)).style(self.theme.normal_style()),
+                    Cell::from(format_cu(stat.p99_cu as f64))
+                        .style(Style::default().fg(self.theme.avg_cu_color(stat.p99_cu as f64))),
                     // Max CU
                     Cell::from(format_cu(stat.max_cu as f64)).style(self.theme.normal_style()),
                     // Total (normal white)
                     Cell::from(format!("{}", stat.total_txs)).style(self.theme.normal_style()),
+                    // Calls (instructions that invoked the program)
+                    Cell::from(format!("{}", stat.total_invocations))
+                        .style(self.theme.normal_style()),
                     // Success% (color coded: green>95%, amber>80%, red<80%)
                     Cell::from(format!("{:.1}%", stat.success_rate))
                         .style(Style::default().fg(success_color)),
+                    // Self Fail% (failures raised by this program, same thresholds)
+                    Cell::from(format!("{:.1}%", stat.program_failure_rate))
+                        .style(Style::default().fg(self_fail_color)),
                 ])
             })
             .collect();
@@ -383,18 +746,24 @@ impl App {
         let table = Table::new(
             rows,
             vec![
-                Constraint::Percentage(30), // Program ID
-                Constraint::Percentage(8),  // Txs/s
-                Constraint::Percentage(9),  // CU/s
-                Constraint::Percentage(9),  // Avg CU
-                Constraint::Percentage(9),  // Min CU
-                Constraint::Percentage(9),  // Max CU
-                Constraint::Percentage(8),  // Total
+                Constraint::Percentage(20), // Program ID
+                Constraint::Percentage(6),  // Txs/s
+                Constraint::Percentage(7),  // CU/s
+                Constraint::Percentage(7),  // Avg CU
+                Constraint::Percentage(6),  // Min CU
+                Constraint::Percentage(6),  // p50
+                Constraint::Percentage(6),  // p90
+                Constraint::Percentage(6),  // p99
+                Constraint::Percentage(6),  // Max CU
+                Constraint::Percentage(7),  // Total
+                Constraint::Percentage(6),  // Calls
                 Constraint::Percentage(8),  // Success%
-                Constraint::Percentage(10), // Padding
+                Constraint::Percentage(8),  // Self Fail%
+                Constraint::Percentage(1),  // Padding
             ],
         )
         .header(header)
+        .row_highlight_style(self.theme.selected_style())
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -403,18 +772,278 @@ impl App {
                 .title_style(self.theme.header_style()),
         );
 
+        // Borders and the header row take 3 lines
+        self.page_size = (area.height as usize).saturating_sub(3).max(1);
+
+        // The table scrolls to keep the selected row in view
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    /// Render the ranked error histogram for the selected program
+    fn render_error_panel(&self, frame: &mut Frame, area: Rect) {
+        let selected = self.selected_stats();
+
+        let title = match selected {
+            Some(stat) => format!(" Errors: {} ", stat.program_id),
+            None => " Errors ".to_string(),
+        };
+        let errors = selected.map(|stat| stat.errors.as_slice()).unwrap_or(&[]);
+        self.render_errors(frame, area, title, errors);
+    }
+
+    /// Render a ranked error histogram
+    fn render_errors(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: String,
+        errors: &[(String, u32)],
+    ) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border_style())
+            .title(title)
+            .title_style(self.theme.header_style());
+
+        if errors.is_empty() {
+            let empty = Paragraph::new("No failures raised by this program in the window")
+                .style(self.theme.muted_style())
+                .block(block);
+            frame.render_widget(empty, area);
+            return;
+        }
+
+        let total: u32 = errors.iter().map(|(_, count)| count).sum();
+        let rows: Vec<Row> = errors
+            .iter()
+            .take(ERROR_PANEL_ROWS)
+            .map(|(error, count)| {
+                Row::new(vec![
+                    Cell::from(format!("{}", count)).style(self.theme.error_style()),
+                    Cell::from(format!("{:.1}%", *count as f64 / total as f64 * 100.0))
+                        .style(self.theme.muted_style()),
+                    Cell::from(error.clone()).style(self.theme.normal_style()),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Length(8), // Count
+                Constraint::Length(7), // Share
+                Constraint::Min(20),   // Error
+            ],
+        )
+        .block(block);
+
         frame.render_widget(table, area);
     }
 
+    /// The view mode and its horizon, e.g. "LIVE 10s" or "WINDOW 1h"
+    fn mode_label(&self) -> String {
+        match self.view_mode {
+            ViewMode::Live => format!("LIVE {}", format_duration(self.live_horizon)),
+            ViewMode::Window => match self.window_horizon() {
+                Some(horizon) => format!("WINDOW {}", format_horizon(horizon)),
+                None => format!(
+                    "WINDOW {}",
+                    format_duration(self.cached_network_stats.window_duration)
+                ),
+            },
+        }
+    }
+
+    /// Render the history and CU distribution of the program in the detail pane
+    fn render_detail(&mut self, frame: &mut Frame, area: Rect) {
+        let program_id = self.detail.as_deref().unwrap_or_default();
+        let title = match program_label(program_id) {
+            Some(label) => format!(" {} ({}) ", label, program_id),
+            None => format!(" {} ", program_id),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border_style())
+            .title(title)
+            .title_style(self.theme.header_style());
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        self.chart_width = (inner.width as usize).max(1);
+
+        let Some(detail) = &self.cached_detail else {
+            let empty = Paragraph::new("No data for this program in the window")
+                .style(self.theme.muted_style());
+            frame.render_widget(empty, inner);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Window totals
+                Constraint::Length(4), // Txs/s
+                Constraint::Length(4), // CU/s
+                Constraint::Length(4), // Success%
+                Constraint::Min(6),    // CU distribution + errors
+            ])
+            .split(inner);
+
+        let summary = Paragraph::new(format!(
+            "{} │ Txs: {} │ Calls: {} │ Success: {:.1}% │ Avg CU: {} │ Min CU: {} │ Max CU: {}",
+            self.mode_label(),
+            detail.total_txs,
+            detail.total_invocations,
+            detail.success_rate,
+            format_cu(detail.avg_cu),
+            format_cu(detail.min_cu as f64),
+            format_cu(detail.max_cu as f64),
+        ))
+        .style(self.theme.normal_style());
+        frame.render_widget(summary, chunks[0]);
+
+        // Sparklines scale to their own peak, so keep two decimals of Txs/s
+        let tps: Vec<f64> = detail
+            .history
+            .iter()
+            .map(|p| p.transactions_per_second)
+            .collect();
+        let tps_data: Vec<u64> = tps.iter().map(|v| (v * 100.0).round() as u64).collect();
+        self.render_sparkline(
+            frame,
+            chunks[1],
+            format!(
+                " Txs/s (now {:.1}, peak {:.1}) ",
+                tps.last().copied().unwrap_or_default(),
+                tps.iter().copied().fold(0.0, f64::max)
+            ),
+            Sparkline::default()
+                .data(&tps_data)
+                .style(Style::default().fg(self.theme.cyan)),
+        );
+
+        let cu: Vec<f64> = detail.history.iter().map(|p| p.cu_per_second).collect();
+        let cu_data: Vec<u64> = cu.iter().map(|v| v.round() as u64).collect();
+        self.render_sparkline(
+            frame,
+            chunks[2],
+            format!(
+                " CU/s (now {}, peak {}) ",
+                format_cu(cu.last().copied().unwrap_or_default()),
+                format_cu(cu.iter().copied().fold(0.0, f64::max))
+            ),
+            Sparkline::default()
+                .data(&cu_data)
+                .style(Style::default().fg(self.theme.amber)),
+        );
+
+        // Gaps where the program had no transactions; scaled to 100%
+        let success_data: Vec<Option<u64>> = detail
+            .history
+            .iter()
+            .map(|p| p.success_rate.map(|rate| rate.round() as u64))
+            .collect();
+        let low = detail
+            .history
+            .iter()
+            .filter_map(|p| p.success_rate)
+            .fold(100.0, f64::min);
+        self.render_sparkline(
+            frame,
+            chunks[3],
+            format!(" Success% (low {:.1}%) ", low),
+            Sparkline::default()
+                .data(&success_data)
+                .max(100)
+                .style(Style::default().fg(self.theme.success_rate_color(low))),
+        );
+
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[4]);
+
+        let bars: Vec<Bar> = detail
+            .distribution
+            .iter()
+            .map(|(label, value)| {
+                Bar::default()
+                    .value(*value)
+                    .label(Line::from(*label))
+                    .text_value(format_cu(*value as f64))
+            })
+            .collect();
+        let distribution = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border_style())
+                    .title(" CU Distribution ")
+                    .title_style(self.theme.header_style()),
+            )
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(6)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(self.theme.cyan))
+            .value_style(
+                Style::default()
+                    .fg(self.theme.background)
+                    .bg(self.theme.cyan),
+            )
+            .label_style(self.theme.muted_style());
+        frame.render_widget(distribution, bottom[0]);
+
+        self.render_errors(frame, bottom[1], " Errors ".to_string(), &detail.errors);
+    }
+
+    /// Render a sparkline under a titled top border
+    fn render_sparkline(&self, frame: &mut Frame, area: Rect, title: String, sparkline: Sparkline) {
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .border_style(self.theme.border_style())
+            .title(title)
+            .title_style(self.theme.normal_style());
+        frame.render_widget(sparkline.block(block), area);
+    }
+
     /// Render the footer with keyboard shortcuts
     fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        // A prompt replaces the shortcuts while it's open
+        if self.searching {
+            let hint = "  (ID or name; Enter keeps it, Esc clears it)";
+            self.render_prompt(frame, area, "/", &self.search, hint, false);
+            return;
+        }
+        if let Some(input) = &self.filter_input {
+            let (hint, is_error) = match &self.filter_error {
+                Some(error) => (format!("  {}", error), true),
+                None => (
+                    "  (e.g. success < 90 && avg_cu > 100k; empty clears)".to_string(),
+                    false,
+                ),
+            };
+            self.render_prompt(frame, area, "\\", input, &hint, is_error);
+            return;
+        }
+
         // htop-style keyboard shortcuts
-        let footer_text = [
-            ("t", "Toggle IDs"),
-            ("u", "Filter System"),
-            ("w", "Window View"),
-            ("q", "Quit"),
-        ];
+        let footer_text: &[(&str, &str)] = if self.detail.is_some() {
+            &[("Esc", "Back"), ("x", "Self CU"), ("q", "Quit")]
+        } else {
+            &[
+                ("Enter", "Details"),
+                ("t", "Toggle IDs"),
+                ("u", "Filter System"),
+                ("/", "Search"),
+                ("\\", "Filter"),
+                ("w", "Live/Window"),
+                ("h", "Horizon"),
+                ("F6", "Sort"),
+                ("r", "Reverse"),
+                ("x", "Self CU"),
+                ("q", "Quit"),
+            ]
+        };
 
         let spans: Vec<Span> = footer_text
             .iter()
@@ -433,12 +1062,76 @@ impl App {
         frame.render_widget(footer, area);
     }
 
+    /// Render a one-line input prompt with a hint (or error) after it
+    fn render_prompt(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        prefix: &str,
+        input: &str,
+        hint: &str,
+        is_error: bool,
+    ) {
+        let hint_style = if is_error {
+            self.theme.error_style()
+        } else {
+            self.theme.muted_style()
+        };
+        let prompt = Paragraph::new(Line::from(vec![
+            Span::styled(prefix.to_string(), self.theme.success_style()),
+            Span::raw(format!("{}_", input)),
+            Span::styled(hint.to_string(), hint_style),
+        ]))
+        .style(Style::default().bg(self.theme.background));
+        frame.render_widget(prompt, area);
+    }
+
     /// Handle keyboard input
     fn handle_key(&mut self, key: KeyCode) {
+        if self.searching {
+            self.handle_search_key(key);
+            return;
+        }
+        if self.filter_input.is_some() {
+            self.handle_filter_key(key);
+            return;
+        }
+
         match key {
-            KeyCode::Char('q') | KeyCode::Esc | KeyCode::F(10) => {
+            KeyCode::Char('q') | KeyCode::F(10) => {
                 self.running = false;
             }
+            KeyCode::Esc => {
+                // Close the detail pane, then clear the search, then quit
+                if self.detail.is_some() {
+                    self.detail = None;
+                    self.cached_detail = None;
+                } else if !self.search.is_empty() {
+                    self.search.clear();
+                } else {
+                    self.running = false;
+                }
+            }
+            KeyCode::Char('/') => {
+                // Open the search prompt (editing the current search)
+                self.searching = true;
+            }
+            KeyCode::Char('\\') => {
+                // Open the filter prompt with the current filter
+                self.filter_input = Some(
+                    self.filter
+                        .as_ref()
+                        .map(|filter| filter.to_string())
+                        .unwrap_or_default(),
+                );
+                self.filter_error = None;
+            }
+            KeyCode::Char('n') => self.jump_to_match(1),
+            KeyCode::Char('N') => self.jump_to_match(-1),
+            KeyCode::Enter => {
+                // Open the detail pane for the selected program
+                self.detail = self.selected_stats().map(|stat| stat.program_id.clone());
+            }
             KeyCode::Char('t') => {
                 // Toggle ID truncation
                 self.truncate_ids = !self.truncate_ids;
@@ -454,27 +1147,177 @@ impl App {
                     ViewMode::Window => ViewMode::Live,
                 };
             }
-            KeyCode::Down => {
-                // TODO: Move selection down (we'll implement this later)
+            KeyCode::Char('h') => {
+                // Cycle the Window horizon: slot window, then each rollup horizon
+                self.window_horizon = match self.window_horizon {
+                    None => Some(0),
+                    Some(i) if i + 1 < WINDOW_HORIZONS.len() => Some(i + 1),
+                    Some(_) => None,
+                };
+                self.view_mode = ViewMode::Window;
+            }
+            KeyCode::F(6) | KeyCode::Char('>') => {
+                // Sort by the next column
+                self.sort_column = self.sort_column.shift(1);
+            }
+            KeyCode::Char('<') => {
+                // Sort by the previous column
+                self.sort_column = self.sort_column.shift(-1);
+            }
+            KeyCode::Char('r') => {
+                // Reverse the sort order
+                self.sort_descending = !self.sort_descending;
             }
-            KeyCode::Up => {
-                // TODO: Move selection up (we'll implement this later)
+            KeyCode::Char('x') => {
+                // Toggle inclusive / exclusive CU
+                self.cu_mode = self.cu_mode.toggle();
             }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(self.page_size as isize),
+            KeyCode::PageUp => self.move_selection(-(self.page_size as isize)),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(usize::MAX),
             _ => {}
         }
     }
 
+    /// Handle keyboard input while the search prompt is open
+    ///
+    /// The table filters as the query is typed.
+    fn handle_search_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Enter => self.searching = false,
+            KeyCode::Esc => {
+                self.searching = false;
+                self.search.clear();
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+            }
+            KeyCode::Char(c) => self.search.push(c),
+            _ => {}
+        }
+    }
+
+    /// Handle keyboard input while the filter prompt is open
+    ///
+    /// Enter applies the filter if it parses (an empty one removes it);
+    /// otherwise the prompt stays open with the error.
+    fn handle_filter_key(&mut self, key: KeyCode) {
+        let Some(input) = &mut self.filter_input else {
+            return;
+        };
+
+        match key {
+            KeyCode::Enter => {
+                if input.trim().is_empty() {
+                    self.filter = None;
+                    self.filter_input = None;
+                    return;
+                }
+                match Filter::parse(input) {
+                    Ok(filter) => {
+                        self.filter = Some(filter);
+                        self.filter_input = None;
+                    }
+                    Err(error) => self.filter_error = Some(error.to_string()),
+                }
+            }
+            KeyCode::Esc => self.filter_input = None,
+            KeyCode::Backspace => {
+                input.pop();
+                self.filter_error = None;
+            }
+            KeyCode::Char(c) => {
+                input.push(c);
+                self.filter_error = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Move the selection to the next (or previous) program matching the
+    /// search, wrapping around the table
+    ///
+    /// `get_stats` only keeps matching rows, so this cycles through them;
+    /// the check skips rows left from before the query last changed.
+    fn jump_to_match(&mut self, direction: isize) {
+        let len = self.cached_stats.len();
+        if len == 0 || self.search.is_empty() {
+            return;
+        }
+
+        let current = self.table_state.selected().unwrap_or(0);
+        let next = (1..=len)
+            .map(|step| (current as isize + direction * step as isize).rem_euclid(len as isize))
+            .map(|idx| idx as usize)
+            .find(|&idx| matches_search(&self.cached_stats[idx].program_id, &self.search));
+        if let Some(idx) = next {
+            self.select(idx);
+        }
+    }
+
+    /// Horizon Window mode is showing, if not the slot window
+    fn window_horizon(&self) -> Option<Duration> {
+        self.window_horizon.map(|i| WINDOW_HORIZONS[i])
+    }
+
+    /// How far back the table and detail pane look, in the current mode
+    fn horizon(&self, stats: &ProgramStats) -> Duration {
+        match (self.view_mode, self.window_horizon()) {
+            (ViewMode::Live, _) => self.live_horizon,
+            (ViewMode::Window, Some(horizon)) => horizon,
+            (ViewMode::Window, None) => stats.window_duration(),
+        }
+    }
+
+    /// Metrics of one program over the current mode's horizon
+    ///
+    /// Live looks at the last few seconds, Window at the slot window or a
+    /// longer horizon from the rollups.
+    fn view<'a>(&self, stats: &'a ProgramStats, now: ChainTime) -> StatsView<'a> {
+        match (self.view_mode, self.window_horizon()) {
+            (ViewMode::Live, _) => stats.recent(self.live_horizon, now),
+            (ViewMode::Window, Some(horizon)) => stats.over(horizon, now),
+            (ViewMode::Window, None) => stats.window(),
+        }
+    }
+
+    /// History of one program over the current horizon, for the detail pane
+    async fn get_detail(&self, program_id: &str) -> Option<ProgramDetail> {
+        let state = self.network_state.read().await;
+        let stats = state.programs.get(program_id)?;
+
+        let view = self.view(stats, state.chain_time());
+        let sketch = view.cu_sketch_for(self.cu_mode);
+        Some(ProgramDetail {
+            history: stats.history(
+                self.chart_width,
+                self.horizon(stats),
+                self.cu_mode,
+                state.current_slot,
+            ),
+            total_txs: view.total_transactions(),
+            total_invocations: view.total_invocations(),
+            success_rate: view.success_rate(),
+            avg_cu: view.avg_cu_per_transaction_for(self.cu_mode),
+            min_cu: view.min_cu_for(self.cu_mode),
+            max_cu: view.max_cu_for(self.cu_mode),
+            distribution: DISTRIBUTION_QUANTILES
+                .iter()
+                .map(|&(label, q)| (label, sketch.quantile(q)))
+                .collect(),
+            errors: view.error_histogram(),
+        })
+    }
+
     /// Get current network statistics
     async fn get_stats(&self) -> (Vec<ProgramStatsDisplay>, NetworkStatsDisplay) {
         let state = self.network_state.read().await;
 
         let mut display = Vec::new();
 
-        // Note: ViewMode (Live vs Window) both read from the same ring buffer
-        // The difference is conceptual - Live shows "streaming" while Window shows "accumulated"
-        // Both calculate from the configured time window stored in the ring buffer
-        // Future enhancement: could adjust time ranges or aggregation methods per mode
-
         // Aggregate network-wide statistics
         let mut total_tps = 0.0;
         let mut total_txs = 0u64;
@@ -486,35 +1329,63 @@ impl App {
             if self.hide_system_programs && is_system_program(program_id) {
                 continue;
             }
+            if !matches_search(program_id, &self.search) {
+                continue;
+            }
 
-            let tx_per_sec = stats.transactions_per_second();
-            let total_program_txs = stats.total_transactions();
-            let success_rate = stats.success_rate();
-            let cu_per_sec = stats.cu_per_second();
-            let avg_cu = stats.avg_cu_per_transaction();
-            let min_cu = stats.min_cu();
-            let max_cu = stats.max_cu();
-
-            // Accumulate network totals
-            total_tps += tx_per_sec;
-            total_txs += total_program_txs as u64;
-            total_success_txs += ((success_rate / 100.0) * total_program_txs as f64) as u64;
-            total_cu_per_sec += cu_per_sec;
+            let view = self.view(stats, state.chain_time());
+            let total_program_txs = view.total_transactions();
+            if total_program_txs == 0 {
+                // Nothing from this program within the horizon
+                continue;
+            }
 
-            display.push(ProgramStatsDisplay {
+            let tx_per_sec = view.transactions_per_second();
+            let total_invocations = view.total_invocations();
+            let success_rate = view.success_rate();
+            let program_failure_rate = view.program_failure_rate();
+            let errors = view.error_histogram();
+            let cu_per_sec = view.cu_per_second_for(self.cu_mode);
+            let avg_cu = view.avg_cu_per_transaction_for(self.cu_mode);
+            let min_cu = view.min_cu_for(self.cu_mode);
+            let max_cu = view.max_cu_for(self.cu_mode);
+            let sketch = view.cu_sketch_for(self.cu_mode);
+
+            let row = ProgramStatsDisplay {
                 program_id: program_id.clone(),
                 tx_per_sec,
                 total_txs: total_program_txs,
+                total_invocations,
                 success_rate,
+                program_failure_rate,
+                errors,
                 cu_per_sec,
                 avg_cu,
                 min_cu,
                 max_cu,
-            });
+                p50_cu: sketch.quantile(0.5),
+                p90_cu: sketch.quantile(0.9),
+                p99_cu: sketch.quantile(0.99),
+            };
+            if self
+                .filter
+                .as_ref()
+                .is_some_and(|filter| !filter.matches(&row))
+            {
+                continue;
+            }
+
+            // Accumulate network totals
+            total_tps += tx_per_sec;
+            total_txs += total_program_txs as u64;
+            total_success_txs += ((success_rate / 100.0) * total_program_txs as f64) as u64;
+            // Inclusive CU would count CPI callees twice
+            total_cu_per_sec += view.cu_per_second_for(CuMode::Exclusive);
+
+            display.push(row);
         }
 
-        // Sort by total_txs descending
-        display.sort_by_key(|s| Reverse(s.total_txs));
+        sort_programs(&mut display, self.sort_column, self.sort_descending);
 
         // Calculate average success rate (weighted)
         let avg_success_rate = if total_txs > 0 {
@@ -527,12 +1398,20 @@ impl App {
             current_slot: state.current_slot,
             latest_network_slot: state.latest_network_slot,
             uptime: state.uptime(),
-            window_duration: state.actual_window(),
+            window_duration: match (self.view_mode, self.window_horizon()) {
+                (ViewMode::Window, Some(horizon)) => state.uptime().min(horizon),
+                _ => state.actual_window(),
+            },
             program_count: state.program_count(),
             total_tps,
             total_txs,
             avg_success_rate,
             total_cu_per_sec,
+            versioned_share: state.versioned_share(),
+            endpoints: state.endpoints.clone(),
+            websocket_connected: state.websocket_connected,
+            blocks_in_flight: state.blocks_in_flight,
+            fetch_concurrency: state.fetch_concurrency,
         };
 
         (display, network_stats)
@@ -544,11 +1423,32 @@ pub struct ProgramStatsDisplay {
     pub program_id: String,
     pub tx_per_sec: f64,
     pub total_txs: u32,
+    pub total_invocations: u32,
     pub success_rate: f64,
+    pub program_failure_rate: f64,
+    pub errors: Vec<(String, u32)>,
     pub cu_per_sec: f64,
     pub avg_cu: f64,
     pub min_cu: u64,
     pub max_cu: u64,
+    pub p50_cu: u64,
+    pub p90_cu: u64,
+    pub p99_cu: u64,
+}
+
+/// Struct for the detail pane of one program, over the current horizon
+pub struct ProgramDetail {
+    /// Activity per run of slots, oldest first, one per chart column
+    pub history: Vec<HistoryPoint>,
+    pub total_txs: u32,
+    pub total_invocations: u32,
+    pub success_rate: f64,
+    pub avg_cu: f64,
+    pub min_cu: u64,
+    pub max_cu: u64,
+    /// (label, CU) at each of DISTRIBUTION_QUANTILES
+    pub distribution: Vec<(&'static str, u64)>,
+    pub errors: Vec<(String, u32)>,
 }
 
 /// Struct for displaying network-wide aggregate statistics
@@ -562,6 +1462,11 @@ pub struct NetworkStatsDisplay {
     pub total_txs: u64,
     pub avg_success_rate: f64,
     pub total_cu_per_sec: f64,
+    pub versioned_share: f64,
+    pub endpoints: Vec<EndpointStatus>,
+    pub websocket_connected: Option<bool>,
+    pub blocks_in_flight: usize,
+    pub fetch_concurrency: usize,
 }
 
 // ============================================================================
@@ -597,6 +1502,16 @@ fn format_cu(n: f64) -> String {
     }
 }
 
+/// Host part of an endpoint URL (hides paths/queries that often carry API keys)
+fn endpoint_host(url: &str) -> String {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    without_scheme
+        .split(['/', '?'])
+        .next()
+        .unwrap_or(without_scheme)
+        .to_string()
+}
+
 /// Format duration in human-readable form (e.g., "2m 34s", "1h 23m")
 fn format_duration(d: Duration) -> String {
     let total_secs = d.as_secs();
@@ -613,3 +1528,328 @@ fn format_duration(d: Duration) -> String {
         format!("{}s", total_secs)
     }
 }
+
+/// Format a horizon compactly (e.g., "15m", "1h", "24h")
+fn format_horizon(d: Duration) -> String {
+    let total_secs = d.as_secs();
+
+    if total_secs >= 3600 && total_secs % 3600 == 0 {
+        format!("{}h", total_secs / 3600)
+    } else if total_secs >= 60 && total_secs % 60 == 0 {
+        format!("{}m", total_secs / 60)
+    } else {
+        format_duration(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_with(programs: &[&str]) -> App {
+        let state = NetworkState::new(Duration::from_secs(300), 750);
+        let mut app = App::new(Arc::new(RwLock::new(state)));
+        app.cached_stats = programs.iter().map(|id| program(id)).collect();
+        app.restore_selection();
+        app
+    }
+
+    fn program(program_id: &str) -> ProgramStatsDisplay {
+        ProgramStatsDisplay {
+            program_id: program_id.to_string(),
+            tx_per_sec: 0.0,
+            total_txs: 1,
+            total_invocations: 1,
+            success_rate: 100.0,
+            program_failure_rate: 0.0,
+            errors: Vec::new(),
+            cu_per_sec: 0.0,
+            avg_cu: 0.0,
+            min_cu: 0,
+            max_cu: 0,
+            p50_cu: 0,
+            p90_cu: 0,
+            p99_cu: 0,
+        }
+    }
+
+    fn selected(app: &App) -> Option<&str> {
+        app.selected_stats().map(|stat| stat.program_id.as_str())
+    }
+
+    #[test]
+    fn test_navigation_keys() {
+        let ids: Vec<String> = (0..50).map(|i| format!("Prog{:02}", i)).collect();
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let mut app = app_with(&ids);
+        app.page_size = 20;
+        assert_eq!(selected(&app), Some("Prog00"));
+
+        app.handle_key(KeyCode::Char('j'));
+        app.handle_key(KeyCode::Down);
+        assert_eq!(selected(&app), Some("Prog02"));
+        app.handle_key(KeyCode::Char('k'));
+        assert_eq!(selected(&app), Some("Prog01"));
+
+        app.handle_key(KeyCode::PageDown);
+        assert_eq!(selected(&app), Some("Prog21"));
+        app.handle_key(KeyCode::PageUp);
+        app.handle_key(KeyCode::PageUp);
+        assert_eq!(selected(&app), Some("Prog00"));
+
+        app.handle_key(KeyCode::End);
+        assert_eq!(selected(&app), Some("Prog49"));
+        app.handle_key(KeyCode::Down);
+        assert_eq!(selected(&app), Some("Prog49"));
+        app.handle_key(KeyCode::Home);
+        assert_eq!(selected(&app), Some("Prog00"));
+    }
+
+    #[test]
+    fn test_selection_follows_program_across_resorts() {
+        let mut app = app_with(&["Jupiter", "Raydium", "Orca"]);
+        app.handle_key(KeyCode::Down);
+        assert_eq!(selected(&app), Some("Raydium"));
+
+        // Raydium overtakes Jupiter
+        app.cached_stats = ["Raydium", "Jupiter", "Orca"].map(program).into();
+        app.restore_selection();
+        assert_eq!(app.table_state.selected(), Some(0));
+        assert_eq!(selected(&app), Some("Raydium"));
+
+        // Once it drops out, the selection stays on the same row
+        app.cached_stats = ["Jupiter", "Orca"].map(program).into();
+        app.restore_selection();
+        assert_eq!(selected(&app), Some("Jupiter"));
+
+        app.cached_stats.clear();
+        app.restore_selection();
+        assert_eq!(selected(&app), None);
+    }
+
+    #[test]
+    fn test_detail_pane_opens_and_closes() {
+        let mut app = app_with(&["Jupiter", "Raydium"]);
+        app.loading = false;
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.detail.as_deref(), Some("Raydium"));
+
+        app.cached_detail = Some(ProgramDetail {
+            history: vec![
+                HistoryPoint {
+                    transactions_per_second: 2.5,
+                    cu_per_second: 125_000.0,
+                    success_rate: Some(80.0),
+                },
+                HistoryPoint::default(),
+            ],
+            total_txs: 5,
+            total_invocations: 5,
+            success_rate: 80.0,
+            avg_cu: 50_000.0,
+            min_cu: 40_000,
+            max_cu: 60_000,
+            distribution: vec![("min", 40_000), ("p50", 50_000), ("max", 60_000)],
+            errors: vec![("SlippageToleranceExceeded (6001)".to_string(), 1)],
+        });
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(140, 40)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Txs/s (now 0.0, peak 2.5)"));
+        assert!(screen.contains("CU Distribution"));
+        assert!(screen.contains("SlippageToleranceExceeded"));
+        assert_eq!(app.chart_width, 138);
+
+        // Esc goes back to the table, then quits
+        app.handle_key(KeyCode::Esc);
+        assert!(app.detail.is_none());
+        assert!(app.running);
+        app.handle_key(KeyCode::Esc);
+        assert!(!app.running);
+    }
+
+    #[tokio::test]
+    async fn test_detail_follows_view_mode() {
+        let mut state = NetworkState::new(Duration::from_secs(300), 750);
+        state.update_slot(1_000);
+        state.record_transaction("Jupiter".to_string(), 10_000, true);
+        state.finish_slot();
+        state.update_slot(1_500);
+        for _ in 0..2 {
+            state.record_transaction("Jupiter".to_string(), 30_000, true);
+        }
+        state.finish_slot();
+        let mut app = App::new(Arc::new(RwLock::new(state)));
+        app.chart_width = 100;
+
+        // The slot window sees every transaction
+        app.view_mode = ViewMode::Window;
+        let detail = app.get_detail("Jupiter").await.unwrap();
+        assert_eq!(detail.total_txs, 3);
+        assert_eq!(detail.min_cu, 10_000);
+        assert_eq!(detail.history.len(), 94);
+
+        // Live only the last 10s, one point per slot
+        app.view_mode = ViewMode::Live;
+        let detail = app.get_detail("Jupiter").await.unwrap();
+        assert_eq!(detail.total_txs, 2);
+        assert_eq!(detail.min_cu, 30_000);
+        assert_eq!(detail.history.len(), 25);
+        assert_eq!(detail.history[24].transactions_per_second, 2.0 / 0.4);
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut app = app_with(&[]);
+        assert_eq!(app.sort_column, SortColumn::Total);
+
+        app.handle_key(KeyCode::Char('>'));
+        assert_eq!(app.sort_column, SortColumn::Calls);
+        app.handle_key(KeyCode::F(6));
+        app.handle_key(KeyCode::F(6));
+        app.handle_key(KeyCode::F(6));
+        assert_eq!(app.sort_column, SortColumn::ProgramId);
+        app.handle_key(KeyCode::Char('<'));
+        assert_eq!(app.sort_column, SortColumn::SelfFail);
+
+        app.handle_key(KeyCode::Char('r'));
+        assert!(!app.sort_descending);
+    }
+
+    #[test]
+    fn test_sort_programs() {
+        let mut rows: Vec<ProgramStatsDisplay> = [
+            ("Orca", 30, 99.0),
+            ("Jupiter", 50, 80.0),
+            ("Raydium", 30, 95.0),
+        ]
+        .into_iter()
+        .map(|(id, total_txs, success_rate)| ProgramStatsDisplay {
+            total_txs,
+            success_rate,
+            ..program(id)
+        })
+        .collect();
+        let order = |rows: &[ProgramStatsDisplay]| -> Vec<String> {
+            rows.iter().map(|r| r.program_id.clone()).collect()
+        };
+
+        // Ties fall back to the program ID either way
+        sort_programs(&mut rows, SortColumn::Total, true);
+        assert_eq!(order(&rows), ["Jupiter", "Orca", "Raydium"]);
+        sort_programs(&mut rows, SortColumn::Total, false);
+        assert_eq!(order(&rows), ["Orca", "Raydium", "Jupiter"]);
+
+        sort_programs(&mut rows, SortColumn::Success, false);
+        assert_eq!(order(&rows), ["Jupiter", "Raydium", "Orca"]);
+        sort_programs(&mut rows, SortColumn::ProgramId, true);
+        assert_eq!(order(&rows), ["Raydium", "Orca", "Jupiter"]);
+    }
+
+    #[test]
+    fn test_matches_search() {
+        let jupiter = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+        assert!(matches_search(jupiter, ""));
+        assert!(matches_search(jupiter, "JUP6"));
+        assert!(matches_search(jupiter, "zcz3"));
+        assert!(matches_search(jupiter, "jupiter"));
+        assert!(!matches_search(jupiter, "orca"));
+        assert!(matches_search(
+            "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc",
+            "Orca"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_search_prompt_and_matches() {
+        let mut state = NetworkState::new(Duration::from_secs(300), 750);
+        state.update_slot(1);
+        for (program_id, txs) in [
+            ("Raydium1", 4),
+            ("Jupiter1", 3),
+            ("Orca1", 2),
+            ("Jupiter2", 1),
+        ] {
+            for _ in 0..txs {
+                state.record_transaction(program_id.to_string(), 10_000, true);
+            }
+        }
+        state.finish_slot();
+        let mut app = App::new(Arc::new(RwLock::new(state)));
+        app.update_stats().await;
+        assert_eq!(app.cached_stats.len(), 4);
+
+        // Typed keys go to the prompt, not the shortcuts
+        app.handle_key(KeyCode::Char('/'));
+        for c in "jupx".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Backspace);
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.search, "jup");
+        assert!(!app.searching);
+        assert!(!app.hide_system_programs);
+        assert_eq!(app.cu_mode, CuMode::Inclusive);
+
+        // Only the matches are left in the table
+        let (rows, _) = app.get_stats().await;
+        let ids: Vec<&str> = rows.iter().map(|row| row.program_id.as_str()).collect();
+        assert_eq!(ids, ["Jupiter1", "Jupiter2"]);
+        app.update_stats().await;
+        assert_eq!(selected(&app), Some("Jupiter1"));
+
+        // n / N cycle through them, wrapping around
+        app.handle_key(KeyCode::Char('n'));
+        assert_eq!(selected(&app), Some("Jupiter2"));
+        app.handle_key(KeyCode::Char('n'));
+        assert_eq!(selected(&app), Some("Jupiter1"));
+        app.handle_key(KeyCode::Char('N'));
+        assert_eq!(selected(&app), Some("Jupiter2"));
+
+        // Esc clears the search before it quits
+        app.handle_key(KeyCode::Esc);
+        assert!(app.search.is_empty());
+        assert!(app.running);
+        app.update_stats().await;
+        assert_eq!(app.cached_stats.len(), 4);
+    }
+
+    #[test]
+    fn test_filter_prompt() {
+        let mut app = app_with(&[]);
+
+        // A bad expression keeps the prompt open with the error
+        app.handle_key(KeyCode::Char('\\'));
+        for c in "tps >".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Enter);
+        assert!(app.filter.is_none());
+        assert_eq!(
+            app.filter_error.as_deref(),
+            Some("expected a number to compare `tps` with (at column 6)")
+        );
+
+        app.handle_key(KeyCode::Char('5'));
+        assert!(app.filter_error.is_none());
+        app.handle_key(KeyCode::Enter);
+        assert!(app.filter_input.is_none());
+        assert_eq!(app.filter.as_ref().unwrap().to_string(), "tps >5");
+
+        // Reopening starts from the current filter; clearing it removes it
+        app.handle_key(KeyCode::Char('\\'));
+        assert_eq!(app.filter_input.as_deref(), Some("tps >5"));
+        for _ in 0..6 {
+            app.handle_key(KeyCode::Backspace);
+        }
+        app.handle_key(KeyCode::Enter);
+        assert!(app.filter.is_none());
+    }
+}
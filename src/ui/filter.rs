@@ -0,0 +1,480 @@
+use super::app::ProgramStatsDisplay;
+use std::fmt;
+
+/// Table columns a filter can compare, by name (first name is the one we suggest)
+const FIELDS: &[(&str, Field)] = &[
+    ("tps", Field::Tps),
+    ("cu_per_sec", Field::CuPerSec),
+    ("cups", Field::CuPerSec),
+    ("avg_cu", Field::AvgCu),
+    ("min_cu", Field::MinCu),
+    ("max_cu", Field::MaxCu),
+    ("p50", Field::P50Cu),
+    ("p90", Field::P90Cu),
+    ("p99", Field::P99Cu),
+    ("total", Field::Total),
+    ("txs", Field::Total),
+    ("calls", Field::Calls),
+    ("success", Field::Success),
+    ("self_fail", Field::SelfFail),
+];
+
+/// A parsed filter expression, e.g. `success < 90 && avg_cu > 100k`
+///
+/// Comparisons of a column against a number, combined with `&&`, `||`, `!`
+/// and parentheses (`!` binds tightest, then `&&`, then `||`). Numbers take
+/// an optional `k`, `m` or `b` suffix, and percentages an optional `%`.
+#[derive(Debug, Clone)]
+pub struct Filter {
+    source: String,
+    expr: Expr,
+}
+
+impl Filter {
+    /// Parse a filter expression
+    pub fn parse(source: &str) -> Result<Self, FilterError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            end: source.len(),
+        };
+        let expr = parser.expr()?;
+
+        // Everything must be consumed: `tps > 5 )` or `tps > 5 calls` are mistakes
+        if let Some((position, token)) = parser.peek() {
+            return Err(FilterError::new(
+                *position,
+                format!("expected `&&` or `||`, found {}", token),
+            ));
+        }
+
+        Ok(Self {
+            source: source.trim().to_string(),
+            expr,
+        })
+    }
+
+    /// Whether a table row passes the filter
+    pub fn matches(&self, row: &ProgramStatsDisplay) -> bool {
+        self.expr.eval(row)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Why a filter expression didn't parse, and where
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterError {
+    /// Byte offset into the expression
+    pub position: usize,
+    pub message: String,
+}
+
+impl FilterError {
+    fn new(position: usize, message: String) -> Self {
+        Self { position, message }
+    }
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at column {})", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+/// Expression tree
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Compare { field: Field, op: Op, value: f64 },
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, row: &ProgramStatsDisplay) -> bool {
+        match self {
+            Expr::Compare { field, op, value } => op.apply(field.value(row), *value),
+            Expr::And(a, b) => a.eval(row) && b.eval(row),
+            Expr::Or(a, b) => a.eval(row) || b.eval(row),
+            Expr::Not(a) => !a.eval(row),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Tps,
+    CuPerSec,
+    AvgCu,
+    MinCu,
+    MaxCu,
+    P50Cu,
+    P90Cu,
+    P99Cu,
+    Total,
+    Calls,
+    Success,
+    SelfFail,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        // Accept the table's header spelling too (`Success%`)
+        let name = name.trim_end_matches('%').to_lowercase();
+        FIELDS
+            .iter()
+            .find(|(field_name, _)| *field_name == name)
+            .map(|(_, field)| *field)
+    }
+
+    /// The column's value, as shown in the table (CU columns follow the CU mode)
+    fn value(self, row: &ProgramStatsDisplay) -> f64 {
+        match self {
+            Field::Tps => row.tx_per_sec,
+            Field::CuPerSec => row.cu_per_sec,
+            Field::AvgCu => row.avg_cu,
+            Field::MinCu => row.min_cu as f64,
+            Field::MaxCu => row.max_cu as f64,
+            Field::P50Cu => row.p50_cu as f64,
+            Field::P90Cu => row.p90_cu as f64,
+            Field::P99Cu => row.p99_cu as f64,
+            Field::Total => row.total_txs as f64,
+            Field::Calls => row.total_invocations as f64,
+            Field::Success => row.success_rate,
+            Field::SelfFail => row.program_failure_rate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Op {
+    fn apply(self, left: f64, right: f64) -> bool {
+        match self {
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "`{}`", name),
+            Token::Number(value) => write!(f, "number {}", value),
+            Token::Op(_) => f.write_str("a comparison"),
+            Token::And => f.write_str("`&&`"),
+            Token::Or => f.write_str("`||`"),
+            Token::Not => f.write_str("`!`"),
+            Token::LParen => f.write_str("`(`"),
+            Token::RParen => f.write_str("`)`"),
+        }
+    }
+}
+
+/// Split an expression into (byte offset, token) pairs
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        // Words and numbers run until the next character that can't be in them
+        if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '%') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let word = &source[start..end];
+            let token =
+                if c.is_ascii_digit() || c == '.' {
+                    Token::Number(parse_number(word).ok_or_else(|| {
+                        FilterError::new(start, format!("invalid number `{}`", word))
+                    })?)
+                } else {
+                    Token::Ident(word.to_string())
+                };
+            tokens.push((start, token));
+            continue;
+        }
+
+        chars.next();
+        let next = chars.peek().map(|&(_, c)| c);
+        let (token, two_chars) = match (c, next) {
+            ('<', Some('=')) => (Token::Op(Op::Le), true),
+            ('>', Some('=')) => (Token::Op(Op::Ge), true),
+            ('=', Some('=')) => (Token::Op(Op::Eq), true),
+            ('!', Some('=')) => (Token::Op(Op::Ne), true),
+            ('&', Some('&')) => (Token::And, true),
+            ('|', Some('|')) => (Token::Or, true),
+            ('<', _) => (Token::Op(Op::Lt), false),
+            ('>', _) => (Token::Op(Op::Gt), false),
+            ('=', _) => (Token::Op(Op::Eq), false),
+            ('!', _) => (Token::Not, false),
+            ('(', _) => (Token::LParen, false),
+            (')', _) => (Token::RParen, false),
+            ('&', _) => return Err(FilterError::new(start, "expected `&&`".to_string())),
+            ('|', _) => return Err(FilterError::new(start, "expected `||`".to_string())),
+            _ => {
+                return Err(FilterError::new(
+                    start,
+                    format!("unexpected character `{}`", c),
+                ))
+            }
+        };
+        if two_chars {
+            chars.next();
+        }
+        tokens.push((start, token));
+    }
+
+    Ok(tokens)
+}
+
+/// Parse a number like `90`, `2.5`, `100k`, `1.5M`, `3b` or `95%`
+fn parse_number(word: &str) -> Option<f64> {
+    let word = word.strip_suffix('%').unwrap_or(word);
+    let (digits, multiplier) = match word.chars().last()?.to_ascii_lowercase() {
+        'k' => (&word[..word.len() - 1], 1e3),
+        'm' => (&word[..word.len() - 1], 1e6),
+        'b' => (&word[..word.len() - 1], 1e9),
+        _ => (word, 1.0),
+    };
+    let value: f64 = digits.parse().ok()?;
+    value.is_finite().then_some(value * multiplier)
+}
+
+/// Recursive descent over the tokens
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    pos: usize,
+
+    /// Where errors at the end of input point
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&(usize, Token)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&(usize, Token)> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    /// Consume the next token if it's `token`
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek().is_some_and(|(_, t)| t == token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// expr := and ( `||` and )*
+    fn expr(&mut self) -> Result<Expr, FilterError> {
+        let mut left = self.and()?;
+        while self.eat(&Token::Or) {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    /// and := unary ( `&&` unary )*
+    fn and(&mut self) -> Result<Expr, FilterError> {
+        let mut left = self.unary()?;
+        while self.eat(&Token::And) {
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    /// unary := `!` unary | `(` expr `)` | field op number
+    fn unary(&mut self) -> Result<Expr, FilterError> {
+        let end = self.end;
+        let Some((position, token)) = self.next().cloned() else {
+            return Err(FilterError::new(
+                end,
+                "expected a comparison like `tps > 5`".to_string(),
+            ));
+        };
+
+        match token {
+            Token::Not => Ok(Expr::Not(Box::new(self.unary()?))),
+            Token::LParen => {
+                let inner = self.expr()?;
+                if !self.eat(&Token::RParen) {
+                    let position = self.peek().map_or(end, |(p, _)| *p);
+                    return Err(FilterError::new(position, "missing `)`".to_string()));
+                }
+                Ok(inner)
+            }
+            Token::Ident(name) => {
+                let field = Field::from_name(&name).ok_or_else(|| {
+                    let names: Vec<&str> = FIELDS.iter().map(|(name, _)| *name).collect();
+                    FilterError::new(
+                        position,
+                        format!("unknown column `{}` (one of: {})", name, names.join(", ")),
+                    )
+                })?;
+                let op = match self.next() {
+                    Some((_, Token::Op(op))) => *op,
+                    other => {
+                        return Err(FilterError::new(
+                            other.map_or(end, |(p, _)| *p),
+                            format!("expected <, <=, >, >=, == or != after `{}`", name),
+                        ))
+                    }
+                };
+                let value = match self.next() {
+                    Some((_, Token::Number(value))) => *value,
+                    other => {
+                        return Err(FilterError::new(
+                            other.map_or(end, |(p, _)| *p),
+                            format!("expected a number to compare `{}` with", name),
+                        ))
+                    }
+                };
+                Ok(Expr::Compare { field, op, value })
+            }
+            token => Err(FilterError::new(
+                position,
+                format!("expected a column name, found {}", token),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(success_rate: f64, avg_cu: f64, tx_per_sec: f64) -> ProgramStatsDisplay {
+        ProgramStatsDisplay {
+            program_id: "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+            tx_per_sec,
+            total_txs: 100,
+            total_invocations: 120,
+            success_rate,
+            program_failure_rate: 100.0 - success_rate,
+            errors: Vec::new(),
+            cu_per_sec: avg_cu * tx_per_sec,
+            avg_cu,
+            min_cu: 0,
+            max_cu: 0,
+            p50_cu: 0,
+            p90_cu: 0,
+            p99_cu: 0,
+        }
+    }
+
+    #[test]
+    fn test_comparisons_and_suffixes() {
+        let busy_failing = row(85.0, 150_000.0, 12.0);
+        let cheap_healthy = row(99.0, 20_000.0, 3.0);
+
+        let filter = Filter::parse("success < 90 && avg_cu > 100k").unwrap();
+        assert!(filter.matches(&busy_failing));
+        assert!(!filter.matches(&cheap_healthy));
+
+        let filter = Filter::parse("tps >= 5").unwrap();
+        assert!(filter.matches(&busy_failing));
+        assert!(!filter.matches(&cheap_healthy));
+
+        assert!(Filter::parse("cups > 1.5M").unwrap().matches(&busy_failing));
+        assert!(Filter::parse("Success% >= 99%")
+            .unwrap()
+            .matches(&cheap_healthy));
+        assert!(Filter::parse("calls == 120 && txs != 99")
+            .unwrap()
+            .matches(&cheap_healthy));
+    }
+
+    #[test]
+    fn test_precedence() {
+        let healthy = row(99.0, 20_000.0, 3.0);
+
+        // && binds tighter than ||
+        assert!(Filter::parse("tps > 1 || tps > 100 && success < 50")
+            .unwrap()
+            .matches(&healthy));
+        assert!(!Filter::parse("(tps > 1 || tps > 100) && success < 50")
+            .unwrap()
+            .matches(&healthy));
+
+        // ! applies to the comparison after it
+        assert!(!Filter::parse("!tps > 1").unwrap().matches(&healthy));
+        assert!(Filter::parse("!(tps > 1 && success < 50)")
+            .unwrap()
+            .matches(&healthy));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |source: &str| Filter::parse(source).unwrap_err();
+
+        assert_eq!(error("succes < 90").position, 0);
+        assert!(error("succes < 90")
+            .message
+            .contains("unknown column `succes`"));
+        assert_eq!(
+            error("tps 5").to_string(),
+            "expected <, <=, >, >=, == or != after `tps` (at column 5)"
+        );
+        assert_eq!(error("tps >").position, 5);
+        assert!(error("tps > fast").message.contains("expected a number"));
+        assert_eq!(error("avg_cu > 10x").message, "invalid number `10x`");
+        assert_eq!(error("tps > 5 & success < 90").message, "expected `&&`");
+        assert_eq!(error("(tps > 5").message, "missing `)`");
+        assert_eq!(
+            error("tps > 5)").message,
+            "expected `&&` or `||`, found `)`"
+        );
+        assert_eq!(error("tps > 5 calls").position, 8);
+        assert_eq!(error("").message, "expected a comparison like `tps > 5`");
+        assert_eq!(error("tps > 5 && #").message, "unexpected character `#`");
+    }
+}
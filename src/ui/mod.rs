@@ -4,7 +4,9 @@
 //! in an interactive terminal dashboard.
 
 mod app;
+mod filter;
 mod theme;
 
 pub use app::App;
+pub use filter::{Filter, FilterError};
 pub use theme::Theme;
@@ -64,6 +64,12 @@ impl Theme {
         Style::default().fg(self.gray)
     }
 
+    pub fn selected_style(&self) -> Style {
+        Style::default()
+            .bg(self.border)
+            .add_modifier(Modifier::BOLD)
+    }
+
     // Get color based on success rate percentage
     pub fn success_rate_color(&self, rate: f64) -> Color {
         if rate >= 95.0 {
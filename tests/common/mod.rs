@@ -0,0 +1,263 @@
+//! Shared helpers for integration tests
+//!
+//! `MockRpc` is a tiny JSON-RPC server speaking just enough HTTP/1.1 for
+//! reqwest. It serves `getSlot` and `getBlock` from a fixture file in
+//! `tests/fixtures/`, including skipped slots, JSON-RPC errors and 429s.
+
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use soltop::stats::NetworkState;
+
+/// A recorded chain slice
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fixture {
+    /// Slot returned by getSlot until the client starts fetching blocks
+    pub start_slot: u64,
+
+    /// Slot returned by getSlot afterwards
+    pub tip_slot: u64,
+
+    pub slots: HashMap<u64, SlotFixture>,
+}
+
+/// How one slot behaves
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SlotFixture {
+    /// Answer with JSON-RPC error -32007 (slot skipped)
+    #[serde(default)]
+    pub skipped: bool,
+
+    /// Answer HTTP 429 this many times before serving the block
+    #[serde(default)]
+    pub rate_limited: u32,
+
+    /// Answer JSON-RPC error -32004 (block not available) this many times first
+    #[serde(default)]
+    pub unavailable: u32,
+
+    /// The getBlock result
+    pub block: Option<Value>,
+}
+
+impl Fixture {
+    /// Load `tests/fixtures/<name>`
+    pub fn load(name: &str) -> Self {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name);
+        let text = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+        serde_json::from_str(&text)
+            .unwrap_or_else(|e| panic!("Failed to parse {}: {}", path.display(), e))
+    }
+}
+
+struct MockState {
+    fixture: Fixture,
+    started_fetching: bool,
+    requests: HashMap<String, usize>,
+    served_errors: HashMap<u64, u32>,
+    always_status: Option<u16>,
+}
+
+/// Mock Solana JSON-RPC endpoint on 127.0.0.1
+pub struct MockRpc {
+    pub url: String,
+    state: Arc<Mutex<MockState>>,
+    task: JoinHandle<()>,
+}
+
+impl MockRpc {
+    /// Serve `fixture` on a random local port
+    pub async fn start(fixture: Fixture) -> Self {
+        Self::spawn(fixture, None).await
+    }
+
+    /// An endpoint that answers every request with `status` (e.g. 503)
+    pub async fn failing(status: u16) -> Self {
+        let fixture = Fixture {
+            start_slot: 0,
+            tip_slot: 0,
+            slots: HashMap::new(),
+        };
+        Self::spawn(fixture, Some(status)).await
+    }
+
+    async fn spawn(fixture: Fixture, always_status: Option<u16>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let state = Arc::new(Mutex::new(MockState {
+            fixture,
+            started_fetching: false,
+            requests: HashMap::new(),
+            served_errors: HashMap::new(),
+            always_status,
+        }));
+
+        let server_state = Arc::clone(&state);
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_connection(stream, Arc::clone(&server_state)));
+            }
+        });
+
+        Self { url, state, task }
+    }
+
+    /// How many requests for `method` were received
+    pub fn requests(&self, method: &str) -> usize {
+        let state = self.state.lock().unwrap();
+        state.requests.get(method).copied().unwrap_or(0)
+    }
+}
+
+impl Drop for MockRpc {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Handle keep-alive HTTP/1.1 requests on one connection
+async fn serve_connection(stream: TcpStream, state: Arc<Mutex<MockState>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    loop {
+        // Request line + headers
+        let mut content_length = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match reader.read_line(&mut line).await {
+                Ok(0) | Err(_) => return,
+                Ok(_) => {}
+            }
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        if reader.read_exact(&mut body).await.is_err() {
+            return;
+        }
+
+        let (status, response) = handle_request(&body, &state);
+        let payload = response.to_string();
+        let reason = match status {
+            200 => "OK",
+            429 => "Too Many Requests",
+            _ => "Error",
+        };
+        let head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nRetry-After: 0\r\n\r\n",
+            status,
+            reason,
+            payload.len()
+        );
+
+        if writer.write_all(head.as_bytes()).await.is_err()
+            || writer.write_all(payload.as_bytes()).await.is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Produce (HTTP status, JSON body) for one JSON-RPC request
+fn handle_request(body: &[u8], state: &Mutex<MockState>) -> (u16, Value) {
+    let request: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+    let method = request["method"].as_str().unwrap_or_default().to_string();
+    let id = request["id"].clone();
+
+    let mut state = state.lock().unwrap();
+    *state.requests.entry(method.clone()).or_insert(0) += 1;
+
+    if let Some(status) = state.always_status {
+        return (status, json!({}));
+    }
+
+    let result = |value: Value| (200, json!({"jsonrpc": "2.0", "result": value, "id": id}));
+    let error = |code: i64, message: &str| {
+        (
+            200,
+            json!({"jsonrpc": "2.0", "error": {"code": code, "message": message}, "id": id}),
+        )
+    };
+
+    match method.as_str() {
+        "getSlot" => {
+            let slot = if state.started_fetching {
+                state.fixture.tip_slot
+            } else {
+                state.fixture.start_slot
+            };
+            result(json!(slot))
+        }
+        "getBlock" => {
+            state.started_fetching = true;
+            let slot = request["params"][0].as_u64().unwrap_or_default();
+            let Some(fixture) = state.fixture.slots.get(&slot).cloned() else {
+                return error(-32004, &format!("Block not available for slot {}", slot));
+            };
+
+            if fixture.skipped {
+                return error(
+                    -32007,
+                    &format!(
+                        "Slot {} was skipped, or missing due to ledger jump to recent snapshot",
+                        slot
+                    ),
+                );
+            }
+
+            // Transient failures first, then the real block
+            let served = state.served_errors.entry(slot).or_insert(0);
+            if *served < fixture.rate_limited {
+                *served += 1;
+                return (429, json!({"error": "rate limited"}));
+            }
+            if *served < fixture.rate_limited + fixture.unavailable {
+                *served += 1;
+                return error(-32004, &format!("Block not available for slot {}", slot));
+            }
+
+            result(fixture.block.unwrap_or(Value::Null))
+        }
+        _ => error(-32601, "Method not found"),
+    }
+}
+
+/// Poll `state` until `current_slot` reaches `slot`
+pub async fn wait_for_slot(state: &Arc<RwLock<NetworkState>>, slot: u64) {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    loop {
+        if state.read().await.current_slot >= slot {
+            return;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "pipeline did not reach slot {}",
+            slot
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
@@ -0,0 +1,131 @@
+//! End-to-end: NetworkMonitor::start against the mock JSON-RPC server
+
+mod common;
+
+use std::time::Duration;
+
+use common::{wait_for_slot, Fixture, MockRpc};
+use soltop::rpc::RetryConfig;
+use soltop::stats::CuMode;
+use soltop::{MonitorConfig, NetworkMonitor};
+
+const JUPITER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+const RAYDIUM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+const ORCA: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+const TOKEN: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const SYSTEM: &str = "11111111111111111111111111111111";
+const ED25519: &str = "Ed25519SigVerify111111111111111111111111111";
+
+fn test_config(rpc_urls: Vec<String>) -> MonitorConfig {
+    MonitorConfig {
+        rpc_urls,
+        poll_interval: Duration::from_millis(10),
+        retry_config: RetryConfig {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(10),
+        },
+        ..MonitorConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn test_pipeline_builds_program_stats() {
+    let mock = MockRpc::start(Fixture::load("basic.json")).await;
+    let monitor = NetworkMonitor::new(test_config(vec![mock.url.clone()]));
+    let state = monitor.get_state();
+
+    let pipeline = tokio::spawn(async move { monitor.start().await });
+    wait_for_slot(&state, 1005).await;
+    pipeline.abort();
+
+    let state = state.read().await;
+    assert_eq!(state.current_slot, 1005);
+    assert_eq!(state.latest_network_slot, 1005);
+
+    // Slot 1000 (one success, one failure) + slot 1003 (retried after -32004)
+    let jupiter = &state.programs[JUPITER];
+    assert_eq!(jupiter.total_transactions(), 3);
+    assert!((jupiter.success_rate() - 200.0 / 3.0).abs() < 1e-9);
+    assert!((jupiter.program_failure_rate() - 100.0 / 3.0).abs() < 1e-9);
+    // Fetched in a burst, but slots 1000 and 1003 are 2s apart by blockTime
+    assert_eq!(jupiter.transactions_per_second(), 1.5);
+    assert_eq!(
+        jupiter.error_histogram(),
+        vec![("SlippageToleranceExceeded (6001)".to_string(), 1)]
+    );
+
+    // Its Token CPIs (3000 and 4000 CU) count towards inclusive CU only
+    assert_eq!(jupiter.max_cu_for(CuMode::Inclusive), 25_000);
+    assert_eq!(jupiter.max_cu_for(CuMode::Exclusive), 21_000);
+    assert_eq!(jupiter.min_cu_for(CuMode::Exclusive), 15_000);
+
+    // Slot 1002 (served after two 429s) + slot 1004
+    let raydium = &state.programs[RAYDIUM];
+    assert_eq!(raydium.total_transactions(), 2);
+    assert_eq!(raydium.success_rate(), 100.0);
+    assert_eq!(raydium.min_cu(), 40_000);
+    assert_eq!(raydium.max_cu(), 60_000);
+    assert_eq!(raydium.avg_cu_per_transaction(), 50_000.0);
+    assert_eq!(raydium.cu_percentile_for(CuMode::Inclusive, 0.0), 40_000);
+    assert_eq!(raydium.cu_percentile_for(CuMode::Inclusive, 0.99), 60_000);
+
+    // Slot 1005: logs truncated mid-instruction, CU recovered from computeUnitsConsumed
+    assert_eq!(state.truncated_log_txs, 1);
+    assert_eq!(state.versioned_txs, 1);
+    assert_eq!(state.legacy_txs, 6);
+    assert_eq!(state.programs[ORCA].max_cu(), 95_000);
+
+    // Slot 1004: builtins and a precompile that never log "consumed"
+    for program_id in [SYSTEM, ED25519] {
+        let program = &state.programs[program_id];
+        assert_eq!(program.total_transactions(), 1);
+        assert_eq!(program.total_invocations(), 1);
+    }
+    assert_eq!(jupiter.total_invocations(), 3);
+
+    // Token is only ever invoked via CPI
+    assert_eq!(state.programs[TOKEN].total_transactions(), 2);
+    assert_eq!(state.programs[TOKEN].total_invocations(), 0);
+}
+
+#[tokio::test]
+async fn test_pipeline_counts_inner_instructions() {
+    let mock = MockRpc::start(Fixture::load("basic.json")).await;
+    let monitor = NetworkMonitor::new(MonitorConfig {
+        count_inner_instructions: true,
+        ..test_config(vec![mock.url.clone()])
+    });
+    let state = monitor.get_state();
+
+    let pipeline = tokio::spawn(async move { monitor.start().await });
+    wait_for_slot(&state, 1005).await;
+    pipeline.abort();
+
+    // Slot 1000 lists the Jupiter -> Token CPI as an inner instruction; in
+    // slot 1005 Token is loaded from a lookup table and its logs were truncated
+    let state = state.read().await;
+    assert_eq!(state.programs[TOKEN].total_invocations(), 2);
+    assert_eq!(state.programs[TOKEN].total_transactions(), 3);
+    assert_eq!(state.programs[JUPITER].total_invocations(), 3);
+}
+
+#[tokio::test]
+async fn test_pipeline_survives_broken_endpoint() {
+    let broken = MockRpc::failing(503).await;
+    let healthy = MockRpc::start(Fixture::load("basic.json")).await;
+
+    let monitor = NetworkMonitor::new(test_config(vec![broken.url.clone(), healthy.url.clone()]));
+    let state = monitor.get_state();
+
+    let pipeline = tokio::spawn(async move { monitor.start().await });
+    wait_for_slot(&state, 1005).await;
+    pipeline.abort();
+
+    let state = state.read().await;
+    assert_eq!(state.programs[RAYDIUM].total_transactions(), 2);
+    assert_eq!(state.programs[JUPITER].total_transactions(), 3);
+
+    let active = state.endpoints.iter().find(|e| e.active).unwrap();
+    assert_eq!(active.url, healthy.url);
+}
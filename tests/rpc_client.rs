@@ -0,0 +1,115 @@
+//! RpcClient / RpcPool against the mock JSON-RPC server
+
+mod common;
+
+use std::time::Duration;
+
+use common::{Fixture, MockRpc};
+use soltop::rpc::{RetryConfig, RpcClient, RpcError, RpcPool};
+
+fn fast_retry() -> RetryConfig {
+    RetryConfig {
+        max_retries: 5,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(10),
+    }
+}
+
+#[tokio::test]
+async fn test_get_slot_and_block() {
+    let mock = MockRpc::start(Fixture::load("basic.json")).await;
+    let client = RpcClient::with_retry_config(mock.url.clone(), fast_retry());
+
+    assert_eq!(client.get_latest_slot().await.unwrap(), 1000);
+
+    let block = client.get_block(1000).await.unwrap().expect("block 1000");
+    assert_eq!(block.transactions.len(), 2);
+}
+
+#[tokio::test]
+async fn test_skipped_slot_is_none() {
+    let mock = MockRpc::start(Fixture::load("basic.json")).await;
+    let client = RpcClient::with_retry_config(mock.url.clone(), fast_retry());
+
+    assert!(client.get_block(1001).await.unwrap().is_none());
+    // Skipped slots are final: no retries
+    assert_eq!(mock.requests("getBlock"), 1);
+}
+
+#[tokio::test]
+async fn test_rate_limit_is_retried() {
+    let mock = MockRpc::start(Fixture::load("basic.json")).await;
+    let client = RpcClient::with_retry_config(mock.url.clone(), fast_retry());
+
+    let block = client.get_block(1002).await.unwrap();
+    assert!(block.is_some());
+    assert_eq!(mock.requests("getBlock"), 3); // 429, 429, 200
+}
+
+#[tokio::test]
+async fn test_block_not_available_is_retried() {
+    let mock = MockRpc::start(Fixture::load("basic.json")).await;
+    let client = RpcClient::with_retry_config(mock.url.clone(), fast_retry());
+
+    assert!(client.get_block(1003).await.unwrap().is_some());
+    assert_eq!(mock.requests("getBlock"), 2);
+}
+
+#[tokio::test]
+async fn test_errors_surface_when_retries_disabled() {
+    let mock = MockRpc::start(Fixture::load("basic.json")).await;
+    let client = RpcClient::with_retry_config(mock.url.clone(), RetryConfig::none());
+
+    match client.get_block(1002).await {
+        Err(RpcError::Http { status: 429, .. }) => {}
+        other => panic!("expected HTTP 429, got {:?}", other),
+    }
+    match client.get_block(1003).await {
+        Err(RpcError::BlockNotAvailable { .. }) => {}
+        other => panic!("expected block not available, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_pool_fails_over_to_healthy_endpoint() {
+    let broken = MockRpc::failing(503).await;
+    let healthy = MockRpc::start(Fixture::load("basic.json")).await;
+
+    let retry = RetryConfig {
+        max_retries: 1,
+        ..fast_retry()
+    };
+    let pool = RpcPool::new(vec![broken.url.clone(), healthy.url.clone()], retry);
+
+    // First request tries the broken endpoint (configuration order), then fails over
+    assert_eq!(pool.get_latest_slot().await.unwrap(), 1000);
+    assert_eq!(broken.requests("getSlot"), 2); // initial + 1 retry
+
+    let status = pool.status();
+    assert!(status[1].active);
+    assert!(status[0].error_rate > 0.0);
+
+    // Once scored, the healthy endpoint is tried first
+    assert!(pool.get_block(1000).await.unwrap().is_some());
+    assert_eq!(broken.requests("getBlock"), 0);
+}
+
+#[tokio::test]
+async fn test_probe_keeps_routed_endpoint_active() {
+    let first = MockRpc::start(Fixture::load("basic.json")).await;
+    let second = MockRpc::start(Fixture::load("basic.json")).await;
+    let pool = RpcPool::new(vec![first.url.clone(), second.url.clone()], fast_retry());
+
+    // Equal scores: requests go to the first endpoint
+    assert_eq!(pool.get_latest_slot().await.unwrap(), 1000);
+    assert!(pool.status()[0].active);
+
+    // The probe measures both (the second last) without rerouting anything
+    pool.probe().await;
+    assert_eq!(second.requests("getSlot"), 1);
+
+    let status = pool.status();
+    assert!(status[0].active);
+    assert!(!status[1].active);
+    assert!(status[1].latency_ms.is_some());
+}